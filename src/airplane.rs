@@ -1,156 +1,233 @@
-use std::f32::consts::PI;
-
-use crate::constant::{self, SPACE_SCALE};
-use bevy::{
-    input::{keyboard::KeyboardInput, ButtonState},
-    math::vec3,
-    prelude::*,
+use crate::{
+    bindings::Action,
+    camera::CameraFocus,
+    clock::{SimulationClock, TimeScale},
+    collision::AirplaneCrashed,
+    constant::{self, SPACE_SCALE},
+    controls::Actions,
+    floating_origin::{PreviousPosition, WorldPosition},
+    gravity::{Gravity, Thrust, Velocity},
+    picking::Pickable,
+    planets::{CelestialBody, Earth, Planets},
+    target_practice::TargetPractice,
+    trail::Trail,
 };
+use bevy::{math::DVec3, prelude::*};
+
+/// the model's nose points along +x with its wings in the xz plane
+const FORWARD: Vec3 = Vec3::X;
+const UP: Vec3 = Vec3::Y;
+const RIGHT: Vec3 = Vec3::Z;
+/// km/s², at full throttle
+const MAX_THRUST: f32 = 2.0;
+/// rad/s while the key is held
+const PITCH_RATE: f32 = 1.0;
+const YAW_RATE: f32 = 0.6;
+const ROLL_RATE: f32 = 1.5;
+/// throttle change per second
+const THROTTLE_RATE: f32 = 0.5;
+/// km above Earth's surface
+const START_ALTITUDE: f64 = 1000.0;
 
+/// a free flying craft, thrust only pushes along its nose and nothing but gravity slows it down
 #[derive(Component)]
 pub struct Airplane {
-    velocity: f32,
-    direction: Vec2,
+    pub attitude: Quat,
+    pub throttle: f32, // 0 to 1
 }
 
 impl Airplane {
-    fn new(volecity: f32) -> Self {
-        Airplane {
-            velocity: volecity,
-            direction: Vec2::new(0.0, PI / 2.),
-        }
+    pub fn forward(&self) -> Vec3 {
+        self.attitude * FORWARD
     }
 }
 
+/// in a circular orbit over Earth's dayside, nose along the way it flies, engine off
+fn start(
+    clock: &SimulationClock,
+    earth: &CelestialBody,
+    gravity: Option<&Gravity>,
+) -> (WorldPosition, Velocity, Airplane) {
+    let seconds = clock.seconds_since_j2000();
+    // Earth orbits the Sun directly, so its own orbit gives where it is and how it moves
+    let (center, center_velocity) = earth.orbit().map_or((DVec3::ZERO, DVec3::ZERO), |orbit| {
+        (
+            orbit.position_at(seconds) * SPACE_SCALE as f64,
+            orbit.velocity_at(seconds) * SPACE_SCALE as f64,
+        )
+    });
+    let up = (-center).try_normalize().unwrap_or(DVec3::X);
+    let along = DVec3::Y.cross(up).try_normalize().unwrap_or(DVec3::Z);
+    let distance = (earth.radius() * SPACE_SCALE) as f64 + START_ALTITUDE;
+    let speed = gravity.map_or(0.0, |gravity| (gravity.0 / distance).sqrt());
+    (
+        WorldPosition(center + up * distance),
+        Velocity(center_velocity + along * speed),
+        Airplane {
+            attitude: Quat::from_rotation_arc(FORWARD, along.as_vec3()),
+            throttle: 0.0,
+        },
+    )
+}
+
+/// places the airplane over Earth once the catalog is loaded, and again when it is reloaded
 pub fn set_plane(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    clock: Res<SimulationClock>,
+    earths: Query<(&CelestialBody, Option<&Gravity>), Added<Earth>>,
+    planes: Query<Entity, With<Airplane>>,
 ) {
-    commands.spawn((
-        SceneBundle {
-            scene: asset_server.load("Airplane.glb#Scene0"),
-            transform: Transform::from_xyz(
-                constant::earth::DISTANCE_TO_SUN * SPACE_SCALE + 100.,
-                0.,
-                0.,
-            ),
-            ..default()
-        },
-        Airplane::new(5.),
-    ));
-    // commands.spawn(PbrBundle {
-    //     mesh: meshes.add(Mesh::from(shape::Plane {
-    //         size: constant::earth::DISTANCE_TO_SUN * SPACE_SCALE + 100.,
-    //         ..Default::default()
-    //     })),
-    //     material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
-    //     ..default()
-    // });
+    let Some((earth, gravity)) = earths.iter().next() else {
+        return;
+    };
+    let (position, velocity, plane) = start(&clock, earth, gravity);
+    let state = (position, PreviousPosition(position.0), velocity, plane);
+    match planes.get_single() {
+        Ok(entity) => {
+            commands.entity(entity).insert(state);
+        }
+        Err(_) => {
+            commands.spawn((
+                SceneBundle {
+                    scene: asset_server.load("Airplane.glb#Scene0"),
+                    ..default()
+                },
+                state,
+                Thrust::default(),
+                Name::new(constant::airplane::NAME),
+                Pickable { radius: 2.0 },
+                Trail::real(30.0, Color::WHITE),
+            ));
+        }
+    }
 }
 
-pub fn control_airplane(time: Res<Time>, mut query: Query<(&mut Transform, &Airplane)>) {
-    for (mut transform, plane) in &mut query {
-        let delta = plane.velocity * time.delta_seconds();
-        transform.translation.y += delta * plane.direction.y.cos();
-        transform.translation.x += delta * plane.direction.y.sin() * plane.direction.x.cos();
-        transform.translation.z += delta * plane.direction.y.sin() * plane.direction.x.sin();
+/// the airplane is flown while the camera follows it or a target practice round runs
+pub fn update_flying(
+    camera_focus: Res<CameraFocus>,
+    game: Res<TargetPractice>,
+    planes: Query<(), With<Airplane>>,
+    mut time_scale: ResMut<TimeScale>,
+) {
+    let flying = game.active
+        || camera_focus
+            .focus
+            .is_some_and(|focus| planes.contains(focus));
+    if time_scale.flying != flying {
+        time_scale.flying = flying;
     }
 }
 
-pub fn airplane_direction(
-    mut key_evr: EventReader<KeyboardInput>,
-    mut query: Query<(&mut Transform, &mut Airplane)>,
+/// keys and gamepad mapping are in `read_key_actions` and `read_gamepad_actions`,
+/// they only work while flying and stop with the clock
+pub fn steer_airplane(
+    clock: Res<SimulationClock>,
+    time_scale: Res<TimeScale>,
+    actions: Res<Actions>,
+    mut planes: Query<&mut Airplane>,
 ) {
-    for ev in key_evr.read() {
-        match ev.state {
-            ButtonState::Pressed => {
-                let mut plane = query.single_mut();
-                let delta_angle = PI / 4.0;
-                match ev.key_code {
-                    Some(KeyCode::Left) => {
-                        plane.1.direction.x -= delta_angle;
-                        plane.0.rotate_y(delta_angle);
-                    }
-                    Some(KeyCode::Right) | Some(KeyCode::D) => {
-                        plane.1.direction.x += delta_angle;
-                        plane.0.rotate_y(-delta_angle);
-                    }
-                    Some(KeyCode::Up) | Some(KeyCode::W) => {
-                        plane.1.direction.y -= delta_angle;
-                        if plane.1.direction.x.sin() >= 0.0 {
-                            plane.0.rotate_z(delta_angle);
-                        } else {
-                            plane.0.rotate_z(-delta_angle);
-                        }
-                    }
-                    Some(KeyCode::Down) | Some(KeyCode::S) => {
-                        plane.1.direction.y += delta_angle;
-                        if plane.1.direction.x.sin() >= 0.0 {
-                            plane.0.rotate_z(-delta_angle);
-                        } else {
-                            plane.0.rotate_z(delta_angle);
-                        }
-                    }
-                    _ => {}
-                };
-            }
-            ButtonState::Released => {}
-        }
+    if !time_scale.flying {
+        return;
+    }
+    let dt = clock.delta_seconds as f32;
+    for mut plane in &mut planes {
+        // rates are about the craft's own axes, so they compose on the right
+        let turn = Quat::from_axis_angle(RIGHT, actions.pitch * PITCH_RATE * dt)
+            * Quat::from_axis_angle(UP, actions.yaw * YAW_RATE * dt)
+            * Quat::from_axis_angle(FORWARD, actions.roll * ROLL_RATE * dt);
+        plane.attitude = (plane.attitude * turn).normalize();
+        plane.throttle = (plane.throttle + actions.throttle * THROTTLE_RATE * dt).clamp(0.0, 1.0);
     }
 }
 
-#[derive(Component, Default)]
-pub struct Bullet {
-    velocity: f32,
-    direction: Vec2,
-    distance: f32,
+/// the airplane is moved with the rest of the craft by `move_craft`,
+/// the engine idles while nobody flies it
+pub fn control_airplane(
+    time_scale: Res<TimeScale>,
+    mut query: Query<(&mut Thrust, &mut Transform, &Airplane)>,
+) {
+    for (mut thrust, mut transform, plane) in &mut query {
+        let throttle = if time_scale.flying {
+            plane.throttle
+        } else {
+            0.0
+        };
+        thrust.0 = (plane.forward() * throttle * MAX_THRUST).as_dvec3();
+        transform.rotation = plane.attitude;
+    }
 }
-const MAX_BULLET_DISTANCE: f32 = 100.;
 
-impl Bullet {
-    fn new(velocity: f32, direction: Vec2) -> Self {
-        Bullet {
-            velocity,
-            direction,
-            ..default()
+/// a crashed airplane starts over in orbit around Earth
+pub fn reset_airplane(
+    mut commands: Commands,
+    mut crashes: EventReader<AirplaneCrashed>,
+    names: Query<&Name>,
+    clock: Res<SimulationClock>,
+    earths: Query<(&CelestialBody, Option<&Gravity>), With<Earth>>,
+    mut planes: Query<(Entity, &mut Trail), With<Airplane>>,
+) {
+    for crash in crashes.read() {
+        if let Ok(name) = names.get(crash.body) {
+            info!("airplane crashed into {}", name);
         }
+        let (Ok((earth, gravity)), Ok((entity, mut trail))) =
+            (earths.get_single(), planes.get_single_mut())
+        else {
+            continue;
+        };
+        let (position, velocity, plane) = start(&clock, earth, gravity);
+        commands
+            .entity(entity)
+            .insert((position, PreviousPosition(position.0), velocity, plane));
+        trail.clear();
     }
 }
 
+#[derive(Component)]
+pub struct Bullet;
+/// km from the airplane, bullets flying further are dropped
+const MAX_BULLET_DISTANCE: f64 = 100.;
+/// km/s, added to the airplane's velocity along its nose
+const MUZZLE_SPEED: f32 = 10.;
+
 pub fn spawn_bullet(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    query: Query<(&Airplane, &Transform)>,
-    keys: ResMut<Input<KeyCode>>,
+    query: Query<(&Airplane, &Transform, &WorldPosition, &Velocity)>,
+    actions: Res<Actions>,
+    time_scale: Res<TimeScale>,
 ) {
-    if keys.just_pressed(KeyCode::A) {
-        let plane = query.single();
+    if !time_scale.flying || !actions.just_pressed(Action::Fire) {
+        return;
+    }
+    if let Ok((plane, transform, position, velocity)) = query.get_single() {
         commands.spawn((
             SceneBundle {
                 scene: asset_server.load("Bullet.glb#Scene0"),
-                transform: plane.1.with_scale(Vec3::splat(0.1)),
+                transform: transform.with_scale(Vec3::splat(0.1)),
                 ..default()
             },
-            Bullet::new(plane.0.velocity + 10., plane.0.direction),
+            *position,
+            PreviousPosition(position.0),
+            Trail::real(2.0, Color::ORANGE),
+            Bullet,
+            Velocity(velocity.0 + (plane.forward() * MUZZLE_SPEED).as_dvec3()),
         ));
-        // keys.reset(KeyCode::A);
     }
 }
 
+/// bullets are moved by `move_craft`, and dropped once out of range of the airplane
 pub fn control_bullet(
     mut commands: Commands,
-    time: Res<Time>,
-    mut query: Query<(&mut Transform, &mut Bullet, Entity)>,
+    planes: Query<&WorldPosition, With<Airplane>>,
+    bullets: Query<(Entity, &WorldPosition), With<Bullet>>,
 ) {
-    for (mut transform, mut bullet, entity) in &mut query {
-        let delta = bullet.velocity * time.delta_seconds();
-        transform.translation.y += delta * bullet.direction.y.cos();
-        transform.translation.x += delta * bullet.direction.y.sin() * bullet.direction.x.cos();
-        transform.translation.z += delta * bullet.direction.y.sin() * bullet.direction.x.sin();
-        bullet.distance += delta;
-        if bullet.distance >= MAX_BULLET_DISTANCE {
+    let Ok(plane) = planes.get_single() else {
+        return;
+    };
+    for (entity, position) in &bullets {
+        if position.0.distance(plane.0) >= MAX_BULLET_DISTANCE {
             commands.entity(entity).despawn_recursive();
         }
     }
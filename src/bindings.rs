@@ -0,0 +1,420 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// user key bindings, only the actions listed in it replace the defaults
+const BINDINGS_PATH: &str = "bindings.ron";
+/// opens the rebinding screen, so it cannot be bound away
+const BINDINGS_SCREEN_KEY: KeyCode = KeyCode::F1;
+
+/// everything the keyboard can do outside of typing a date
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Action {
+    PitchUp,
+    PitchDown,
+    YawLeft,
+    YawRight,
+    RollLeft,
+    RollRight,
+    ThrottleUp,
+    ThrottleDown,
+    Fire,
+    FocusNext,
+    FocusPrevious,
+    Pause,
+    Faster,
+    Slower,
+    Reverse,
+    JumpToDate,
+    Now,
+    CycleScale,
+    ToggleLabels,
+    ToggleTrails,
+    ToggleRotationAxes,
+    ToggleEclipticGrid,
+    ToggleNodes,
+    ToggleApsides,
+    ToggleConstellations,
+    ToggleShadows,
+    TargetPractice,
+}
+
+impl Action {
+    pub const ALL: [Action; 27] = [
+        Action::PitchUp,
+        Action::PitchDown,
+        Action::YawLeft,
+        Action::YawRight,
+        Action::RollLeft,
+        Action::RollRight,
+        Action::ThrottleUp,
+        Action::ThrottleDown,
+        Action::Fire,
+        Action::FocusNext,
+        Action::FocusPrevious,
+        Action::Pause,
+        Action::Faster,
+        Action::Slower,
+        Action::Reverse,
+        Action::JumpToDate,
+        Action::Now,
+        Action::CycleScale,
+        Action::ToggleLabels,
+        Action::ToggleTrails,
+        Action::ToggleRotationAxes,
+        Action::ToggleEclipticGrid,
+        Action::ToggleNodes,
+        Action::ToggleApsides,
+        Action::ToggleConstellations,
+        Action::ToggleShadows,
+        Action::TargetPractice,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::PitchUp => "Pitch up",
+            Action::PitchDown => "Pitch down",
+            Action::YawLeft => "Yaw left",
+            Action::YawRight => "Yaw right",
+            Action::RollLeft => "Roll left",
+            Action::RollRight => "Roll right",
+            Action::ThrottleUp => "Throttle up",
+            Action::ThrottleDown => "Throttle down",
+            Action::Fire => "Fire",
+            Action::FocusNext => "Focus next",
+            Action::FocusPrevious => "Focus previous",
+            Action::Pause => "Pause",
+            Action::Faster => "Faster",
+            Action::Slower => "Slower",
+            Action::Reverse => "Reverse time",
+            Action::JumpToDate => "Jump to date",
+            Action::Now => "Jump to now",
+            Action::CycleScale => "Change scale",
+            Action::ToggleLabels => "Labels",
+            Action::ToggleTrails => "Trails",
+            Action::ToggleRotationAxes => "Rotation axes",
+            Action::ToggleEclipticGrid => "Ecliptic grid",
+            Action::ToggleNodes => "Orbit nodes",
+            Action::ToggleApsides => "Periapsis and apoapsis",
+            Action::ToggleConstellations => "Constellations",
+            Action::ToggleShadows => "Shadows",
+            Action::TargetPractice => "Target practice",
+        }
+    }
+
+    fn default_keys(&self) -> Vec<KeyCode> {
+        match self {
+            Action::PitchUp => vec![KeyCode::W, KeyCode::Up],
+            Action::PitchDown => vec![KeyCode::S, KeyCode::Down],
+            Action::YawLeft => vec![KeyCode::A, KeyCode::Left],
+            Action::YawRight => vec![KeyCode::D, KeyCode::Right],
+            Action::RollLeft => vec![KeyCode::Q],
+            Action::RollRight => vec![KeyCode::E],
+            Action::ThrottleUp => vec![KeyCode::ShiftLeft],
+            Action::ThrottleDown => vec![KeyCode::ControlLeft],
+            Action::Fire => vec![KeyCode::F],
+            Action::FocusNext => vec![KeyCode::PageDown],
+            Action::FocusPrevious => vec![KeyCode::PageUp],
+            Action::Pause => vec![KeyCode::Space],
+            Action::Faster => vec![KeyCode::Equals, KeyCode::NumpadAdd],
+            Action::Slower => vec![KeyCode::Minus, KeyCode::NumpadSubtract],
+            Action::Reverse => vec![KeyCode::R],
+            Action::JumpToDate => vec![KeyCode::J],
+            Action::Now => vec![KeyCode::T],
+            Action::CycleScale => vec![KeyCode::M],
+            Action::ToggleLabels => vec![KeyCode::H],
+            Action::ToggleTrails => vec![KeyCode::O],
+            Action::ToggleRotationAxes => vec![KeyCode::X],
+            Action::ToggleEclipticGrid => vec![KeyCode::G],
+            Action::ToggleNodes => vec![KeyCode::N],
+            Action::ToggleApsides => vec![KeyCode::P],
+            Action::ToggleConstellations => vec![KeyCode::C],
+            Action::ToggleShadows => vec![KeyCode::L],
+            Action::TargetPractice => vec![KeyCode::B],
+        }
+    }
+}
+
+/// keys of every action, saved like `({ Fire: [F], YawLeft: [A, Left] })`
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct Bindings(pub BTreeMap<Action, Vec<KeyCode>>);
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Bindings(
+            Action::ALL
+                .iter()
+                .map(|action| (*action, action.default_keys()))
+                .collect(),
+        )
+    }
+}
+
+impl Bindings {
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.0.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// the keys as shown to the user, like `W/Up`
+    pub fn label(&self, action: Action) -> String {
+        let keys = self.keys(action);
+        if keys.is_empty() {
+            return "unbound".into();
+        }
+        keys.iter()
+            .map(|key| format!("{key:?}"))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// the other actions sharing a key with this one
+    pub fn conflicts(&self, action: Action) -> Vec<Action> {
+        let keys = self.keys(action);
+        Action::ALL
+            .into_iter()
+            .filter(|other| {
+                *other != action && self.keys(*other).iter().any(|key| keys.contains(key))
+            })
+            .collect()
+    }
+
+    fn load() -> Self {
+        let mut bindings = Bindings::default();
+        let text = match std::fs::read_to_string(BINDINGS_PATH) {
+            Ok(text) => text,
+            Err(_) => return bindings,
+        };
+        match ron::de::from_str::<Bindings>(&text) {
+            Ok(user) => bindings.0.extend(user.0),
+            Err(error) => warn!("ignoring key bindings in {}: {}", BINDINGS_PATH, error),
+        }
+        for keys in bindings.0.values_mut() {
+            if keys.contains(&BINDINGS_SCREEN_KEY) {
+                warn!(
+                    "{:?} opens the key bindings and cannot be bound",
+                    BINDINGS_SCREEN_KEY
+                );
+                keys.retain(|key| *key != BINDINGS_SCREEN_KEY);
+            }
+        }
+        bindings
+    }
+
+    fn save(&self) {
+        let text = match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()) {
+            Ok(text) => text,
+            Err(error) => {
+                warn!("could not write key bindings: {}", error);
+                return;
+            }
+        };
+        if let Err(error) = std::fs::write(BINDINGS_PATH, text) {
+            warn!(
+                "could not save key bindings to {}: {}",
+                BINDINGS_PATH, error
+            );
+        }
+    }
+}
+
+pub fn load_bindings(mut commands: Commands) {
+    let bindings = Bindings::load();
+    for action in Action::ALL {
+        let conflicts = bindings.conflicts(action);
+        if !conflicts.is_empty() {
+            warn!(
+                "{} shares a key with {}",
+                action.label(),
+                conflicts
+                    .iter()
+                    .map(|other| other.label())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    }
+    commands.insert_resource(bindings);
+}
+
+/// the rebinding screen, keyboard actions are off while it is open
+#[derive(Resource, Default)]
+pub struct BindingScreen {
+    pub open: bool,
+    /// the action whose next key press becomes its binding
+    waiting: Option<Action>,
+}
+
+#[derive(Component)]
+pub struct BindingScreenRoot;
+
+#[derive(Component)]
+pub struct BindingRow(Action);
+
+pub fn setup_binding_screen(mut commands: Commands) {
+    let text_style = TextStyle {
+        font_size: 16.0,
+        color: Color::rgb(0.9, 0.9, 0.9),
+        ..default()
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    display: Display::None,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.85).into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            BindingScreenRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    format!(
+                        "Key bindings, saved to {BINDINGS_PATH}\n\
+                         click an action and press its key, [Back] unbinds it, \
+                         [Escape] cancels, [{BINDINGS_SCREEN_KEY:?}] closes"
+                    ),
+                    TextStyle {
+                        font_size: 20.0,
+                        ..text_style.clone()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(12.0)),
+                    ..default()
+                }),
+            );
+            for action in Action::ALL {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(520.0),
+                                padding: UiRect::axes(Val::Px(8.0), Val::Px(2.0)),
+                                ..default()
+                            },
+                            background_color: Color::NONE.into(),
+                            ..default()
+                        },
+                        BindingRow(action),
+                    ))
+                    .with_children(|row| {
+                        row.spawn(TextBundle::from_section("", text_style.clone()));
+                    });
+            }
+        });
+}
+
+/// `F1` opens and closes the rebinding screen
+pub fn toggle_binding_screen(
+    keys: Res<Input<KeyCode>>,
+    mut screen: ResMut<BindingScreen>,
+    mut roots: Query<&mut Style, With<BindingScreenRoot>>,
+) {
+    if !keys.just_pressed(BINDINGS_SCREEN_KEY) {
+        return;
+    }
+    screen.open = !screen.open;
+    screen.waiting = None;
+    for mut style in &mut roots {
+        style.display = if screen.open {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+pub fn click_binding_row(
+    mut screen: ResMut<BindingScreen>,
+    rows: Query<(&Interaction, &BindingRow), Changed<Interaction>>,
+) {
+    for (interaction, row) in &rows {
+        if *interaction == Interaction::Pressed {
+            screen.waiting = Some(row.0);
+        }
+    }
+}
+
+/// the key pressed after clicking a row replaces that action's keys, and the bindings are saved
+pub fn capture_binding(
+    keys: Res<Input<KeyCode>>,
+    mut screen: ResMut<BindingScreen>,
+    mut bindings: ResMut<Bindings>,
+) {
+    let Some(action) = screen.waiting else {
+        return;
+    };
+    let Some(key) = keys
+        .get_just_pressed()
+        .find(|key| **key != BINDINGS_SCREEN_KEY)
+    else {
+        return;
+    };
+    screen.waiting = None;
+    let keys = match key {
+        KeyCode::Escape => return,
+        KeyCode::Back => Vec::new(),
+        key => vec![*key],
+    };
+    bindings.0.insert(action, keys);
+    bindings.save();
+}
+
+pub fn update_binding_rows(
+    bindings: Res<Bindings>,
+    screen: Res<BindingScreen>,
+    rows: Query<(&BindingRow, &Interaction, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    if !screen.open {
+        return;
+    }
+    for (row, interaction, children) in &rows {
+        let action = row.0;
+        let conflicts = bindings.conflicts(action);
+        let (value, color) = if screen.waiting == Some(action) {
+            (
+                format!("{}: press a key", action.label()),
+                Color::rgb(1.0, 0.8, 0.3),
+            )
+        } else if !conflicts.is_empty() {
+            let others: Vec<_> = conflicts.iter().map(|other| other.label()).collect();
+            (
+                format!(
+                    "{}: {}  (also {})",
+                    action.label(),
+                    bindings.label(action),
+                    others.join(", ")
+                ),
+                Color::rgb(1.0, 0.35, 0.35),
+            )
+        } else if *interaction == Interaction::Hovered {
+            (
+                format!("{}: {}", action.label(), bindings.label(action)),
+                Color::WHITE,
+            )
+        } else {
+            (
+                format!("{}: {}", action.label(), bindings.label(action)),
+                Color::rgb(0.7, 0.7, 0.7),
+            )
+        };
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value = value.clone();
+                text.sections[0].style.color = color;
+            }
+        }
+    }
+}
@@ -1,70 +1,62 @@
-use crate::{constant, CameraFocus};
+use crate::{
+    camera::{find_target, CameraFocus, NamedTargets},
+    catalog::{BodyCatalog, BodyCatalogHandle},
+    constant,
+};
 use bevy::prelude::*;
 
 #[derive(Component)]
 pub enum ChangeViewButton {
-    Sun,
-    Mercury,
-    Venus,
-    Earth,
-    Moon,
-    Mars,
-    Jupiter,
-    Saturn,
-    Uranus,
-    Neptune,
+    Body(String),
     Airplane,
-    Global,
 }
 
 impl ChangeViewButton {
     pub fn name(&self) -> &str {
-        match *self {
-            ChangeViewButton::Sun => constant::sun::NAME,
-            ChangeViewButton::Mercury => constant::mercury::NAME,
-            ChangeViewButton::Venus => constant::venus::NAME,
-            ChangeViewButton::Earth => constant::earth::NAME,
-            ChangeViewButton::Moon => constant::moon::NAME,
-            ChangeViewButton::Mars => constant::mars::NAME,
-            ChangeViewButton::Jupiter => constant::jupiter::NAME,
-            ChangeViewButton::Saturn => constant::saturn::NAME,
-            ChangeViewButton::Uranus => constant::uranus::NAME,
-            ChangeViewButton::Neptune => constant::neptune::NAME,
+        match self {
+            ChangeViewButton::Body(name) => name,
             ChangeViewButton::Airplane => constant::airplane::NAME,
-            ChangeViewButton::Global => "Global",
         }
     }
 }
 
-pub fn setup_button(mut commands: Commands) {
+#[derive(Component)]
+pub struct ViewButtonBar;
+
+/// one button per catalog body, rebuilt whenever the catalog is (re)loaded
+pub fn setup_view_buttons(
+    mut commands: Commands,
+    catalog_handle: Res<BodyCatalogHandle>,
+    catalogs: Res<Assets<BodyCatalog>>,
+    mut events: EventReader<AssetEvent<BodyCatalog>>,
+    bars: Query<Entity, With<ViewButtonBar>>,
+) {
+    let Some(catalog) = catalog_handle.loaded(&mut events, &catalogs) else {
+        return;
+    };
+    for bar in &bars {
+        commands.entity(bar).despawn_recursive();
+    }
+
     commands
         .spawn(NodeBundle {
             style: Style {
                 width: Val::Percent(100.0),
                 height: Val::Percent(100.0),
                 align_items: AlignItems::FlexEnd,
+                align_content: AlignContent::FlexEnd,
                 justify_content: JustifyContent::FlexEnd,
+                flex_wrap: FlexWrap::Wrap,
                 ..default()
             },
             ..default()
         })
+        .insert(ViewButtonBar)
         .with_children(|parent| {
-            let btns = vec![
-                ChangeViewButton::Sun,
-                ChangeViewButton::Mercury,
-                ChangeViewButton::Venus,
-                ChangeViewButton::Earth,
-                ChangeViewButton::Moon,
-                ChangeViewButton::Mars,
-                ChangeViewButton::Jupiter,
-                ChangeViewButton::Saturn,
-                ChangeViewButton::Uranus,
-                ChangeViewButton::Neptune,
-                ChangeViewButton::Airplane,
-            ];
-            for btn in btns {
-                add_button(parent, btn);
+            for body in &catalog.bodies {
+                add_button(parent, ChangeViewButton::Body(body.name.clone()));
             }
+            add_button(parent, ChangeViewButton::Airplane);
         });
 }
 
@@ -112,6 +104,7 @@ pub fn handle_button(
         Changed<Interaction>,
     >,
     mut camera_focus: ResMut<CameraFocus>,
+    targets: NamedTargets,
 ) {
     for (interaction, mut color, mut border_color, btn) in &mut interaction_query {
         match *interaction {
@@ -119,7 +112,7 @@ pub fn handle_button(
                 // text.sections[0].value = "Press".to_string();
                 // *color = Color::rgb(0.35, 0.75, 0.35).into();
                 border_color.0 = Color::RED;
-                camera_focus.focus = btn.name().into();
+                camera_focus.focus = find_target(&targets, btn.name());
             }
             Interaction::Hovered => {
                 *color = Color::rgb(0.25, 0.25, 0.25).into();
@@ -135,7 +128,7 @@ pub fn handle_button(
 
 pub fn mouse_button_input(buttons: Res<Input<MouseButton>>, mut camera_focus: ResMut<CameraFocus>) {
     if buttons.pressed(MouseButton::Right) {
-        camera_focus.focus = ChangeViewButton::Global.name().into();
+        camera_focus.focus = None;
     }
     // we can check multiple at once with `.any_*`
     if buttons.any_just_pressed([MouseButton::Right, MouseButton::Middle]) {
@@ -175,7 +168,7 @@ pub fn scroll_events(mut scroll_evr: EventReader<MouseWheel>) {
                 );
             }
             MouseScrollUnit::Pixel => {
-                // camera_focus.focus = ChangeViewButton::Global.name().into();
+                // camera_focus.focus = None;
                 // println!(
                 //     "Scroll (pixel units): vertical: {}, horizontal: {}",
                 //     ev.y, ev.x
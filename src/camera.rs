@@ -1,54 +1,164 @@
-use bevy::{math::vec3, prelude::*};
+use bevy::{math::DVec3, prelude::*};
 use bevy_panorbit_camera::PanOrbitCamera;
 
 use crate::{
     airplane::Airplane,
-    constant,
-    planets::{CommonPlanets, Earth, Planets},
-    CameraFocus, Moon,
+    floating_origin::{FloatingOrigin, WorldPosition},
+    picking::Pickable,
+    planets::CelestialBody,
+    scale::ScaledPosition,
 };
 
+/// the entity the camera orbits around, `None` for the free global view
+#[derive(Resource, Default)]
+pub struct CameraFocus {
+    pub focus: Option<Entity>,
+}
+
+/// what can be focused by name, the nodes inside a body's model carry names too
+pub type NamedTargets<'w, 's> =
+    Query<'w, 's, (Entity, &'static Name), Or<(With<CelestialBody>, With<Airplane>)>>;
+
+pub fn find_target(targets: &NamedTargets, name: &str) -> Option<Entity> {
+    targets
+        .iter()
+        .find(|(_, target)| target.as_str() == name)
+        .map(|(entity, _)| entity)
+}
+
+#[derive(Resource)]
+pub struct CameraSettings {
+    /// seconds to fly from one focus to the next
+    pub transition_duration: f32,
+    /// orbit radius as a multiple of the focused body's radius
+    pub focus_radius_factor: f32,
+    /// orbit radius for focus targets that are not celestial bodies, like the airplane
+    pub default_focus_radius: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            transition_duration: 1.5,
+            focus_radius_factor: 4.0,
+            default_focus_radius: 10.0,
+        }
+    }
+}
+
+pub struct FocusTransition {
+    from_focus: DVec3,
+    from_radius: f32,
+    to_radius: f32,
+    elapsed: f32,
+}
+
+#[derive(Default)]
+pub struct FocusState {
+    entity: Option<Entity>,
+    /// rendered radius of the focused body, to follow it when the scale mode resizes it
+    body_radius: f32,
+    transition: Option<FocusTransition>,
+}
+
+type FocusTargets<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static WorldPosition,
+        Option<&'static ScaledPosition>,
+        Option<&'static Pickable>,
+        Has<CelestialBody>,
+    ),
+>;
+
+/// flies the camera to the focused entity and keeps the floating origin under the camera focus,
+/// so the orbit camera always turns around `Vec3::ZERO` in render space
 pub fn control_camera(
+    time: Res<Time>,
     camera_focus: Res<CameraFocus>,
-    mut camera: Query<(&mut PanOrbitCamera, &mut Transform)>,
-    earth: Query<(&Earth, &Transform), Without<PanOrbitCamera>>,
-    moon: Query<(&Moon, &Transform), Without<PanOrbitCamera>>,
-    planets: Query<(&CommonPlanets, &Transform), Without<PanOrbitCamera>>,
-    airplane: Query<(&Airplane, &Transform), Without<PanOrbitCamera>>,
+    settings: Res<CameraSettings>,
+    mut origin: ResMut<FloatingOrigin>,
+    mut camera: Query<&mut PanOrbitCamera>,
+    targets: FocusTargets,
+    mut state: Local<FocusState>,
 ) {
-    let mut target = Vec3::ZERO;
-    match camera_focus.focus.as_str() {
-        constant::earth::NAME => {
-            let earth = earth.single();
-            target += earth.1.translation;
-        }
-        constant::moon::NAME => {
-            let moon = moon.single();
-            target += moon.1.translation;
-        }
-        constant::airplane::NAME => {
-            let plane = airplane.single();
-            target = plane.1.translation;
-            let mut camera = camera.single_mut();
-            // camera.1.translation = target + vec3(10., 10., 10.);
-            camera.0.target_focus = target;
-            return;
-        }
-        "Global" => {
-            return;
+    let state = &mut *state;
+    let mut camera = camera.single_mut();
+    let Some((position, scaled, pickable, is_body)) =
+        camera_focus.focus.and_then(|focus| targets.get(focus).ok())
+    else {
+        state.entity = None;
+        state.transition = None;
+        // free camera: move the origin along when panning
+        let shift = camera.focus;
+        if shift != Vec3::ZERO {
+            origin.0 = origin.to_world(shift);
+            camera.focus = Vec3::ZERO;
+            camera.target_focus -= shift;
+            camera.force_update = true;
         }
-        _ => {
-            // let plane = query.single();
-            for (planet, transform) in &planets {
-                if planet.name() == camera_focus.focus {
-                    target += transform.translation;
-                }
+        return;
+    };
+    let target = scaled.map_or(position.0, |scaled| scaled.0);
+    let body_radius = match (is_body, pickable) {
+        (true, Some(pickable)) => pickable.radius,
+        _ => 0.0,
+    };
+
+    if state.entity != camera_focus.focus {
+        state.entity = camera_focus.focus;
+        state.body_radius = body_radius;
+        let to_radius = if body_radius > 0.0 {
+            body_radius * settings.focus_radius_factor
+        } else {
+            settings.default_focus_radius
+        };
+        state.transition = Some(FocusTransition {
+            from_focus: origin.to_world(camera.focus),
+            from_radius: camera.radius.unwrap_or(to_radius),
+            to_radius,
+            elapsed: 0.0,
+        });
+    } else if body_radius != state.body_radius && state.body_radius > 0.0 {
+        // keep the same view of the body while the scale mode resizes it
+        let ratio = body_radius / state.body_radius;
+        state.body_radius = body_radius;
+        match state.transition.as_mut() {
+            Some(t) => t.to_radius *= ratio,
+            None => {
+                camera.radius = camera.radius.map(|radius| radius * ratio);
+                camera.target_radius *= ratio;
             }
         }
     }
-    // let mut camera = set.p0();
-    let mut camera = camera.single_mut();
-    let delta_translation = Vec3::new(50.0, 50.0, 50.0);
-    // camera.1.translation = target + delta_translation;
-    camera.0.target_focus = target;
+
+    let mut focus = target;
+    if let Some(t) = state.transition.as_mut() {
+        t.elapsed += time.delta_seconds();
+        let progress = ease_in_out_cubic(
+            (t.elapsed / settings.transition_duration.max(f32::EPSILON)).min(1.0),
+        );
+        focus = t.from_focus.lerp(target, progress as f64);
+        // interpolate in log space, radii range from a few km to billions
+        let radius =
+            (t.from_radius.ln() + (t.to_radius.ln() - t.from_radius.ln()) * progress).exp();
+        camera.radius = Some(radius);
+        camera.target_radius = radius;
+        if progress >= 1.0 {
+            state.transition = None;
+        }
+    }
+    origin.0 = focus;
+    camera.focus = Vec3::ZERO;
+    camera.target_focus = Vec3::ZERO;
+    camera.force_update = true;
+}
+
+pub fn ease_in_out_cubic(t: f32) -> f32 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
 }
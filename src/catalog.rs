@@ -0,0 +1,310 @@
+use std::f32::consts::PI;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::{BoxedFuture, HashMap},
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    camera::CameraFocus,
+    constant::{self, ONE_DAY_SECONDS, PLANET_GLTF_SCALE, SPACE_SCALE},
+    floating_origin::{PreviousPosition, WorldPosition},
+    gravity::Gravity,
+    orbit::{equatorial_to_world, OrbitalElements},
+    picking::Pickable,
+    planets::{CelestialBody, Earth, OrbitsParent, Star},
+    scale::ScaledPosition,
+    trail::Trail,
+};
+
+pub const CATALOG_PATH: &str = "solar_system.catalog.ron";
+
+/// every celestial body of the simulation, see `assets/solar_system.catalog.ron`
+#[derive(Asset, TypePath, Deserialize)]
+pub struct BodyCatalog {
+    pub bodies: Vec<BodyDescriptor>,
+}
+
+#[derive(Deserialize)]
+pub struct BodyDescriptor {
+    pub name: String,
+    #[serde(default)]
+    pub parent: Option<String>,
+    pub radius: f32, // km
+    /// gravitational parameter, km³/s²
+    #[serde(default)]
+    pub gm: Option<f64>,
+    #[serde(default)]
+    pub orbit: Option<OrbitDescriptor>,
+    pub rotation_period: f32, // day, negative for retrograde rotation
+    /// right ascension and declination of the north pole on the J2000 equator, degree
+    #[serde(default)]
+    pub pole: Option<(f64, f64)>,
+    pub model: String,
+    #[serde(default)]
+    pub color: Option<(u8, u8, u8)>,
+    #[serde(default)]
+    pub orbit_color: Option<(u8, u8, u8)>,
+    #[serde(default)]
+    pub rings: Option<RingDescriptor>,
+    /// day, a quarter of the orbital period by default
+    #[serde(default)]
+    pub trail_days: Option<f64>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct RingDescriptor {
+    pub color: (u8, u8, u8),
+    pub bands: Vec<RingBand>,
+}
+
+/// one ring, or a gap inside one, seen as a uniform opacity
+#[derive(Deserialize, Clone, Copy)]
+pub struct RingBand {
+    pub inner: f32, // km
+    pub outer: f32, // km
+    pub opacity: f32,
+}
+
+impl RingDescriptor {
+    pub fn inner_radius(&self) -> f32 {
+        self.bands
+            .iter()
+            .map(|band| band.inner)
+            .fold(f32::MAX, f32::min)
+    }
+
+    pub fn outer_radius(&self) -> f32 {
+        self.bands.iter().map(|band| band.outer).fold(0.0, f32::max)
+    }
+}
+
+/// orbital elements as written in the catalog, angles in degrees
+#[derive(Deserialize, Clone, Copy)]
+pub struct OrbitDescriptor {
+    pub semi_major_axis: f64, // km
+    pub eccentricity: f64,
+    pub inclination: f64,
+    pub longitude_of_ascending_node: f64,
+    pub argument_of_periapsis: f64,
+    pub mean_anomaly_at_epoch: f64,
+    pub orbital_period: f64, // day
+}
+
+impl OrbitDescriptor {
+    pub fn elements(&self) -> OrbitalElements {
+        OrbitalElements::new(
+            self.semi_major_axis,
+            self.eccentricity,
+            self.inclination,
+            self.longitude_of_ascending_node,
+            self.argument_of_periapsis,
+            self.mean_anomaly_at_epoch,
+            self.orbital_period,
+        )
+    }
+}
+
+impl BodyDescriptor {
+    pub fn rotation_velocity(&self) -> f32 {
+        2.0 * PI / (self.rotation_period * ONE_DAY_SECONDS)
+    }
+
+    /// north pole in world coordinates, the orbit normal for bodies without pole data
+    pub fn pole(&self) -> Vec3 {
+        match (self.pole, self.orbit) {
+            (Some((right_ascension, declination)), _) => {
+                equatorial_to_world(right_ascension, declination).as_vec3()
+            }
+            (None, Some(orbit)) => orbit.elements().normal().as_vec3(),
+            (None, None) => Vec3::Y,
+        }
+    }
+
+    /// tint of the fallback sphere, grey when the catalog has none
+    pub fn color(&self) -> Color {
+        self.color
+            .map_or(Color::GRAY, |(r, g, b)| Color::rgb_u8(r, g, b))
+    }
+
+    pub fn orbit_color(&self) -> Option<Color> {
+        self.orbit_color.map(|(r, g, b)| Color::rgb_u8(r, g, b))
+    }
+
+    /// bodies without an orbit stay put and leave no trail
+    pub fn trail(&self) -> Option<Trail> {
+        let orbit = self.orbit?;
+        let days = self.trail_days.unwrap_or(orbit.orbital_period / 4.0);
+        Some(Trail::simulated(
+            days,
+            self.orbit_color().unwrap_or(self.color()),
+        ))
+    }
+}
+
+#[derive(Default)]
+pub struct BodyCatalogLoader;
+
+#[derive(Debug, Error)]
+pub enum BodyCatalogLoaderError {
+    #[error("could not read body catalog: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse body catalog: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for BodyCatalogLoader {
+    type Asset = BodyCatalog;
+    type Settings = ();
+    type Error = BodyCatalogLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<BodyCatalog, BodyCatalogLoaderError>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(ron::de::from_bytes(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["catalog.ron"]
+    }
+}
+
+#[derive(Resource)]
+pub struct BodyCatalogHandle(pub Handle<BodyCatalog>);
+
+impl BodyCatalogHandle {
+    /// the catalog, once per (re)load
+    pub fn loaded<'a>(
+        &self,
+        events: &mut EventReader<AssetEvent<BodyCatalog>>,
+        catalogs: &'a Assets<BodyCatalog>,
+    ) -> Option<&'a BodyCatalog> {
+        let loaded = events
+            .read()
+            .filter(
+                |ev| matches!(ev, AssetEvent::LoadedWithDependencies { id } if *id == self.0.id()),
+            )
+            .count();
+        if loaded > 0 {
+            catalogs.get(&self.0)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn spawn_bodies(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    catalog_handle: Res<BodyCatalogHandle>,
+    catalogs: Res<Assets<BodyCatalog>>,
+    mut events: EventReader<AssetEvent<BodyCatalog>>,
+    mut camera_focus: ResMut<CameraFocus>,
+    previous: Query<(Entity, &Name), With<CelestialBody>>,
+) {
+    let Some(catalog) = catalog_handle.loaded(&mut events, &catalogs) else {
+        return;
+    };
+
+    // a reload replaces the bodies, the camera stays on the one with the same name
+    let mut focused = None;
+    for (entity, name) in &previous {
+        if camera_focus.focus == Some(entity) {
+            focused = Some(name.as_str());
+        }
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let mut spawned = HashMap::new();
+    for body in &catalog.bodies {
+        let orbit = body.orbit.map(|orbit| orbit.elements());
+        let mut entity = commands.spawn((
+            SceneBundle {
+                scene: asset_server.load(format!("{}#Scene0", body.model)),
+                transform: Transform::from_scale(Vec3::splat(
+                    PLANET_GLTF_SCALE * SPACE_SCALE * body.radius,
+                )),
+                ..default()
+            },
+            WorldPosition::default(),
+            PreviousPosition::default(),
+            ScaledPosition::default(),
+            Name::new(body.name.clone()),
+            CelestialBody::new(
+                body.radius,
+                body.rotation_velocity(),
+                body.pole(),
+                orbit,
+                body.name.clone(),
+            ),
+            Pickable {
+                radius: body.radius * SPACE_SCALE,
+            },
+        ));
+        if let Some(trail) = body.trail() {
+            entity.insert(trail);
+        }
+        if let Some(gm) = body.gm {
+            entity.insert(Gravity(gm));
+        }
+        if orbit.is_none() {
+            entity.insert(Star);
+        }
+        if body.name == constant::earth::NAME {
+            entity.insert(Earth);
+        }
+        if focused == Some(body.name.as_str()) {
+            camera_focus.focus = Some(entity.id());
+        }
+        spawned.insert(body.name.as_str(), entity.id());
+    }
+
+    let parents: HashMap<&str, &str> = catalog
+        .bodies
+        .iter()
+        .filter_map(|body| Some((body.name.as_str(), body.parent.as_deref()?)))
+        .collect();
+    for body in &catalog.bodies {
+        let Some(parent) = &body.parent else {
+            continue;
+        };
+        // a body may not end up orbiting itself, directly or through its parents
+        let mut ancestor = Some(parent.as_str());
+        let mut cyclic = false;
+        for _ in 0..catalog.bodies.len() {
+            let Some(name) = ancestor else {
+                break;
+            };
+            if name == body.name {
+                cyclic = true;
+                break;
+            }
+            ancestor = parents.get(name).copied();
+        }
+        if cyclic {
+            warn!(
+                "{} orbits itself through {}, ignoring its parent",
+                body.name, parent
+            );
+            continue;
+        }
+        match spawned.get(parent.as_str()) {
+            Some(parent) => {
+                commands
+                    .entity(spawned[body.name.as_str()])
+                    .insert(OrbitsParent(*parent));
+            }
+            None => warn!("{} orbits unknown body {}", body.name, parent),
+        }
+    }
+}
@@ -0,0 +1,466 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::{prelude::*, window::ReceivedCharacter};
+
+use crate::{
+    bindings::{Action, Bindings},
+    constant::ONE_DAY_SECONDS,
+    controls::Actions,
+};
+
+pub const J2000: f64 = 2_451_545.0; // 2000-01-01 12:00
+const UNIX_EPOCH_JD: f64 = 2_440_587.5; // 1970-01-01 00:00
+/// simulated seconds per real second
+const RATES: [f64; 8] = [0.1, 1., 10., 100., 1e3, 1e4, 1e5, 1e6];
+const DEFAULT_RATE_INDEX: usize = 5; // ~8.6s => 1day
+
+/// absolute simulation time, every orbit is derived from it
+#[derive(Resource)]
+pub struct SimulationClock {
+    pub julian_date: f64,
+    /// simulated seconds the clock ran this frame, jumps in time do not count
+    pub delta_seconds: f64,
+}
+
+impl SimulationClock {
+    pub fn j2000() -> Self {
+        Self {
+            julian_date: J2000,
+            delta_seconds: 0.0,
+        }
+    }
+
+    pub fn now() -> Self {
+        let unix_seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or_default();
+        Self {
+            julian_date: UNIX_EPOCH_JD + unix_seconds / ONE_DAY_SECONDS as f64,
+            delta_seconds: 0.0,
+        }
+    }
+
+    pub fn seconds_since_j2000(&self) -> f64 {
+        (self.julian_date - J2000) * ONE_DAY_SECONDS as f64
+    }
+
+    pub fn jump_to(&mut self, year: i32, month: u32, day: u32) {
+        self.julian_date = julian_date(year, month, day as f64);
+    }
+
+    /// (year, month, day, hour, minute)
+    pub fn calendar_date(&self) -> (i32, u32, u32, u32, u32) {
+        let z = (self.julian_date + 0.5).floor();
+        let f = self.julian_date + 0.5 - z;
+        let a = if z < 2_299_161.0 {
+            z
+        } else {
+            let alpha = ((z - 1_867_216.25) / 36_524.25).floor();
+            z + 1.0 + alpha - (alpha / 4.0).floor()
+        };
+        let b = a + 1524.0;
+        let c = ((b - 122.1) / 365.25).floor();
+        let d = (365.25 * c).floor();
+        let e = ((b - d) / 30.6001).floor();
+
+        let day = b - d - (30.6001 * e).floor();
+        let month = if e < 14.0 { e - 1.0 } else { e - 13.0 };
+        let year = if month > 2.0 { c - 4716.0 } else { c - 4715.0 };
+        let minutes = (f * 24.0 * 60.0).floor() as u32;
+        (
+            year as i32,
+            month as u32,
+            day as u32,
+            minutes / 60,
+            minutes % 60,
+        )
+    }
+}
+
+/// Julian date at 00:00 of the given calendar day (Meeus, Astronomical Algorithms ch. 7)
+pub fn julian_date(year: i32, month: u32, day: f64) -> f64 {
+    let (y, m) = if month <= 2 {
+        (year as f64 - 1.0, month as f64 + 12.0)
+    } else {
+        (year as f64, month as f64)
+    };
+    let b = if (year, month, day) >= (1582, 10, 15.0) {
+        let a = (y / 100.0).floor();
+        2.0 - a + (a / 4.0).floor()
+    } else {
+        0.0
+    };
+    (365.25 * (y + 4716.0)).floor() + (30.6001 * (m + 1.0)).floor() + day + b - 1524.5
+}
+
+#[derive(Resource)]
+pub struct TimeScale {
+    rate_index: usize,
+    pub paused: bool,
+    pub reversed: bool,
+    /// the airplane is being flown, which runs the clock at x1 forward so the craft and
+    /// the bodies move on the same timebase at a speed they can be steered at
+    pub flying: bool,
+}
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self {
+            rate_index: DEFAULT_RATE_INDEX,
+            paused: false,
+            reversed: false,
+            flying: false,
+        }
+    }
+}
+
+impl TimeScale {
+    /// simulated seconds per real second, negative when running backwards
+    pub fn rate(&self) -> f64 {
+        if self.paused {
+            0.0
+        } else if self.flying {
+            1.0
+        } else if self.reversed {
+            -RATES[self.rate_index]
+        } else {
+            RATES[self.rate_index]
+        }
+    }
+
+    pub fn faster(&mut self) {
+        self.rate_index = (self.rate_index + 1).min(RATES.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.rate_index = self.rate_index.saturating_sub(1);
+    }
+
+    pub fn label(&self) -> String {
+        let label = if self.flying {
+            "x1 (flying)".to_string()
+        } else {
+            let sign = if self.reversed { "-" } else { "" };
+            format!("{sign}x{}", RATES[self.rate_index])
+        };
+        if self.paused {
+            label + " (paused)"
+        } else {
+            label
+        }
+    }
+}
+
+pub fn advance_clock(
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    mut clock: ResMut<SimulationClock>,
+) {
+    clock.delta_seconds = time.delta_seconds_f64() * time_scale.rate();
+    clock.julian_date += clock.delta_seconds / ONE_DAY_SECONDS as f64;
+}
+
+#[derive(Component)]
+pub struct ClockText;
+
+#[derive(Component, Clone, Copy)]
+pub enum TimeControlButton {
+    Reverse,
+    Slower,
+    Pause,
+    Faster,
+}
+
+impl TimeControlButton {
+    fn label(&self) -> &str {
+        match *self {
+            TimeControlButton::Reverse => "<>",
+            TimeControlButton::Slower => "-",
+            TimeControlButton::Pause => "||",
+            TimeControlButton::Faster => "+",
+        }
+    }
+
+    /// only pausing works while flying
+    fn apply(&self, time_scale: &mut TimeScale) {
+        match *self {
+            TimeControlButton::Pause => time_scale.paused = !time_scale.paused,
+            _ if time_scale.flying => {}
+            TimeControlButton::Reverse => time_scale.reversed = !time_scale.reversed,
+            TimeControlButton::Slower => time_scale.slower(),
+            TimeControlButton::Faster => time_scale.faster(),
+        }
+    }
+}
+
+/// text typed after pressing `J`, `None` when not editing
+#[derive(Resource, Default)]
+pub struct DateInput {
+    buffer: Option<String>,
+}
+
+impl DateInput {
+    pub fn is_editing(&self) -> bool {
+        self.buffer.is_some()
+    }
+}
+
+pub fn setup_clock(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                left: Val::Px(10.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(5.0),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                        ..default()
+                    },
+                ),
+                ClockText,
+            ));
+            parent.spawn(NodeBundle::default()).with_children(|parent| {
+                let btns = [
+                    TimeControlButton::Reverse,
+                    TimeControlButton::Slower,
+                    TimeControlButton::Pause,
+                    TimeControlButton::Faster,
+                ];
+                for btn in btns {
+                    add_time_control_button(parent, btn);
+                }
+            });
+        });
+}
+
+fn add_time_control_button(cmd: &mut ChildBuilder, btn: TimeControlButton) {
+    let label = String::from(btn.label());
+    cmd.spawn((
+        ButtonBundle {
+            style: Style {
+                width: Val::Px(40.0),
+                height: Val::Px(30.0),
+                border: UiRect::all(Val::Px(3.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            border_color: BorderColor(Color::GRAY),
+            background_color: Color::BLACK.into(),
+            ..default()
+        },
+        btn,
+    ))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            label,
+            TextStyle {
+                font_size: 18.0,
+                color: Color::rgb(0.9, 0.9, 0.9),
+                ..default()
+            },
+        ));
+    });
+}
+
+pub fn handle_time_control_button(
+    mut interaction_query: Query<
+        (
+            &Interaction,
+            &mut BackgroundColor,
+            &mut BorderColor,
+            &TimeControlButton,
+        ),
+        Changed<Interaction>,
+    >,
+    mut time_scale: ResMut<TimeScale>,
+) {
+    for (interaction, mut color, mut border_color, btn) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                border_color.0 = Color::RED;
+                btn.apply(&mut time_scale);
+            }
+            Interaction::Hovered => {
+                *color = Color::rgb(0.25, 0.25, 0.25).into();
+                border_color.0 = Color::WHITE;
+            }
+            Interaction::None => {
+                *color = Color::BLACK.into();
+                border_color.0 = Color::GRAY;
+            }
+        }
+    }
+}
+
+/// `Space` pause, `=` faster, `-` slower, `R` reverse
+/// actions are off while a date is typed, see `read_key_actions`
+pub fn time_control_keys(actions: Res<Actions>, mut time_scale: ResMut<TimeScale>) {
+    for (action, button) in [
+        (Action::Pause, TimeControlButton::Pause),
+        (Action::Faster, TimeControlButton::Faster),
+        (Action::Slower, TimeControlButton::Slower),
+        (Action::Reverse, TimeControlButton::Reverse),
+    ] {
+        if actions.just_pressed(action) {
+            button.apply(&mut time_scale);
+        }
+    }
+}
+
+pub fn update_clock_text(
+    clock: Res<SimulationClock>,
+    time_scale: Res<TimeScale>,
+    date_input: Res<DateInput>,
+    bindings: Res<Bindings>,
+    mut query: Query<&mut Text, With<ClockText>>,
+) {
+    let (year, month, day, hour, minute) = clock.calendar_date();
+    let mut value = format!(
+        "{year:04}-{month:02}-{day:02} {hour:02}:{minute:02} UTC  {}",
+        time_scale.label()
+    );
+    match &date_input.buffer {
+        Some(buffer) => value += &format!("\nJump to (YYYY-MM-DD): {buffer}_"),
+        None => {
+            value += &format!(
+                "\n[{}] jump to date  [{}] now  [{}] pause  [{} {}] speed  [{}] reverse",
+                bindings.label(Action::JumpToDate),
+                bindings.label(Action::Now),
+                bindings.label(Action::Pause),
+                bindings.label(Action::Slower),
+                bindings.label(Action::Faster),
+                bindings.label(Action::Reverse),
+            )
+        }
+    }
+    for mut text in &mut query {
+        text.sections[0].value = value.clone();
+    }
+}
+
+/// typing the date reads keys directly, whatever they are bound to
+pub fn jump_to_date(
+    keys: Res<Input<KeyCode>>,
+    actions: Res<Actions>,
+    mut char_evr: EventReader<ReceivedCharacter>,
+    mut date_input: ResMut<DateInput>,
+    mut clock: ResMut<SimulationClock>,
+) {
+    let Some(buffer) = date_input.buffer.as_mut() else {
+        char_evr.clear();
+        if actions.just_pressed(Action::JumpToDate) {
+            date_input.buffer = Some(String::new());
+        } else if actions.just_pressed(Action::Now) {
+            *clock = SimulationClock::now();
+        }
+        return;
+    };
+
+    for ev in char_evr.read() {
+        if ev.char.is_ascii_digit() || (ev.char == '-' && !buffer.is_empty()) {
+            buffer.push(ev.char);
+        }
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        buffer.pop();
+    }
+    if keys.just_pressed(KeyCode::Return) {
+        match parse_date(buffer) {
+            Some((year, month, day)) => clock.jump_to(year, month, day),
+            None => warn!("invalid date: {buffer}"),
+        }
+        date_input.buffer = None;
+    } else if keys.just_pressed(KeyCode::Escape) {
+        date_input.buffer = None;
+    }
+}
+
+fn parse_date(s: &str) -> Option<(i32, u32, u32)> {
+    let mut parts = s.splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next().map_or(Some(1), |d| d.parse().ok())?;
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+    Some((year, month, day))
+}
+
+/// Julian calendar before the Gregorian reform, like `julian_date`
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let leap = if year > 1582 {
+        year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+    } else {
+        year % 4 == 0
+    };
+    match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_date_checks_month_length() {
+        assert_eq!(parse_date("2023-01-31"), Some((2023, 1, 31)));
+        assert_eq!(parse_date("2023-02-28"), Some((2023, 2, 28)));
+        assert_eq!(parse_date("2023-02-29"), None);
+        assert_eq!(parse_date("2023-02-31"), None);
+        assert_eq!(parse_date("2023-04-31"), None);
+        assert_eq!(parse_date("2024-02-29"), Some((2024, 2, 29)));
+        assert_eq!(parse_date("2000-02-29"), Some((2000, 2, 29)));
+        assert_eq!(parse_date("1900-02-29"), None);
+    }
+
+    #[test]
+    fn parse_date_formats() {
+        assert_eq!(parse_date("1969-07"), Some((1969, 7, 1)));
+        assert_eq!(parse_date("2023-13-01"), None);
+        assert_eq!(parse_date("2023-00-10"), None);
+        assert_eq!(parse_date("2023-05-00"), None);
+        assert_eq!(parse_date("2023"), None);
+        assert_eq!(parse_date(""), None);
+    }
+
+    #[test]
+    fn julian_date_of_known_days() {
+        assert_eq!(julian_date(2000, 1, 1.5), J2000);
+        assert_eq!(julian_date(1970, 1, 1.0), UNIX_EPOCH_JD);
+        assert_eq!(julian_date(1957, 10, 4.81), 2_436_116.31);
+    }
+
+    #[test]
+    fn calendar_date_round_trip() {
+        for (year, month, day) in [
+            (2000, 1, 1),
+            (2023, 2, 28),
+            (2024, 2, 29),
+            (2024, 3, 1),
+            (1999, 12, 31),
+            (1582, 10, 15),
+            (1066, 10, 14),
+        ] {
+            let mut clock = SimulationClock::j2000();
+            clock.jump_to(year, month, day);
+            assert_eq!(clock.calendar_date(), (year, month, day, 0, 0));
+        }
+    }
+}
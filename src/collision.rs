@@ -0,0 +1,220 @@
+use bevy::{math::DVec3, prelude::*};
+
+use crate::{
+    airplane::{Airplane, Bullet},
+    floating_origin::{FloatingOrigin, PreviousPosition, WorldPosition},
+    planets::{CelestialBody, Planets},
+};
+
+const IMPACT_SECONDS: f32 = 1.5;
+/// km, final radius of the flash
+const BULLET_IMPACT_SIZE: f32 = 100.0;
+const CRASH_IMPACT_SIZE: f32 = 1000.0;
+
+#[derive(Event)]
+pub struct BulletHit {
+    pub bullet: Entity,
+    pub body: Entity,
+    /// from the body's center to the point of impact
+    pub direction: Vec3,
+}
+
+#[derive(Event)]
+pub struct AirplaneCrashed {
+    pub body: Entity,
+    pub direction: Vec3,
+}
+
+/// flash growing and fading on a body's surface
+#[derive(Component)]
+pub struct Impact {
+    body: Entity,
+    direction: Vec3,
+    size: f32,
+    age: f32,
+}
+
+type Craft<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static WorldPosition,
+        &'static PreviousPosition,
+        Has<Airplane>,
+        Has<Bullet>,
+    ),
+>;
+
+/// tests the path covered this frame relative to each body, so neither fast bullets nor
+/// bodies moving at a high time rate can skip through each other; bodies collide at their
+/// physical size, whatever the scale mode draws
+pub fn detect_collisions(
+    bodies: Query<(Entity, &WorldPosition, &PreviousPosition, &CelestialBody)>,
+    craft: Craft,
+    mut hits: EventWriter<BulletHit>,
+    mut crashes: EventWriter<AirplaneCrashed>,
+) {
+    for (entity, position, previous, airplane, bullet) in &craft {
+        let Some((body, direction)) =
+            bodies
+                .iter()
+                .find_map(|(body, center, center_previous, celestial)| {
+                    hit_direction(
+                        previous.0 - center_previous.0,
+                        position.0 - center.0,
+                        DVec3::ZERO,
+                        celestial.radius() as f64,
+                    )
+                    .map(|direction| (body, direction))
+                })
+        else {
+            continue;
+        };
+        if bullet {
+            hits.send(BulletHit {
+                bullet: entity,
+                body,
+                direction,
+            });
+        }
+        if airplane {
+            crashes.send(AirplaneCrashed { body, direction });
+        }
+    }
+}
+
+/// where the segment first comes within `radius` of `center`, as seen from the center
+pub fn hit_direction(from: DVec3, to: DVec3, center: DVec3, radius: f64) -> Option<Vec3> {
+    let path = to - from;
+    let start = from - center;
+    // smallest `along` in 0..=1 with |start + path * along| = radius
+    let along = if start.length_squared() <= radius * radius {
+        0.0
+    } else {
+        let a = path.length_squared();
+        let b = start.dot(path);
+        let c = start.length_squared() - radius * radius;
+        let discriminant = b * b - a * c;
+        if a == 0.0 || b >= 0.0 || discriminant < 0.0 {
+            return None;
+        }
+        let along = (-b - discriminant.sqrt()) / a;
+        if along > 1.0 {
+            return None;
+        }
+        along
+    };
+    let direction = (start + path * along).normalize_or_zero();
+    let direction = if direction == DVec3::ZERO {
+        -path.normalize_or_zero()
+    } else {
+        direction
+    };
+    Some(direction.as_vec3())
+}
+
+pub fn spawn_impacts(
+    mut commands: Commands,
+    mut hits: EventReader<BulletHit>,
+    mut crashes: EventReader<AirplaneCrashed>,
+) {
+    for hit in hits.read() {
+        commands.entity(hit.bullet).despawn_recursive();
+        commands.spawn(Impact {
+            body: hit.body,
+            direction: hit.direction,
+            size: BULLET_IMPACT_SIZE,
+            age: 0.0,
+        });
+    }
+    for crash in crashes.read() {
+        commands.spawn(Impact {
+            body: crash.body,
+            direction: crash.direction,
+            size: CRASH_IMPACT_SIZE,
+            age: 0.0,
+        });
+    }
+}
+
+pub fn draw_impacts(
+    mut commands: Commands,
+    time: Res<Time>,
+    origin: Res<FloatingOrigin>,
+    mut impacts: Query<(Entity, &mut Impact)>,
+    bodies: Query<(&WorldPosition, &CelestialBody)>,
+    mut gizmos: Gizmos,
+) {
+    for (entity, mut impact) in &mut impacts {
+        impact.age += time.delta_seconds();
+        let progress = impact.age / IMPACT_SECONDS;
+        let Ok((center, celestial)) = bodies.get(impact.body) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        if progress >= 1.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        // on the surface that was hit, like the craft it is drawn where it really is
+        let position =
+            origin.to_render(center.0 + impact.direction.as_dvec3() * celestial.radius() as f64);
+        let color = Color::rgb(1.0, 0.6, 0.1).with_a(1.0 - progress);
+        gizmos.sphere(
+            position,
+            Quat::IDENTITY,
+            impact.size * progress.sqrt(),
+            color,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CENTER: DVec3 = DVec3::new(100.0, 0.0, 0.0);
+    const RADIUS: f64 = 10.0;
+
+    #[test]
+    fn starting_inside_hits_at_the_start() {
+        let direction = hit_direction(DVec3::new(95.0, 0.0, 0.0), DVec3::ZERO, CENTER, RADIUS);
+        assert_eq!(direction, Some(Vec3::NEG_X));
+    }
+
+    #[test]
+    fn passing_through_hits_on_the_near_side() {
+        let from = DVec3::new(0.0, 5.0, 0.0);
+        let to = DVec3::new(200.0, 5.0, 0.0);
+        let direction = hit_direction(from, to, CENTER, RADIUS).unwrap();
+        let expected = DVec3::new(-(RADIUS * RADIUS - 25.0).sqrt(), 5.0, 0.0) / RADIUS;
+        assert!(direction.distance(expected.as_vec3()) < 1e-6);
+        // head on, it hits the side facing the start
+        let direction = hit_direction(DVec3::ZERO, CENTER * 2.0, CENTER, RADIUS);
+        assert_eq!(direction, Some(Vec3::NEG_X));
+    }
+
+    #[test]
+    fn tangent_miss() {
+        let from = DVec3::new(0.0, RADIUS + 0.1, 0.0);
+        let to = DVec3::new(200.0, RADIUS + 0.1, 0.0);
+        assert_eq!(hit_direction(from, to, CENTER, RADIUS), None);
+    }
+
+    #[test]
+    fn stopping_short_misses() {
+        let to = DVec3::new(100.0 - RADIUS - 1.0, 0.0, 0.0);
+        assert_eq!(hit_direction(DVec3::ZERO, to, CENTER, RADIUS), None);
+    }
+
+    #[test]
+    fn zero_length_path() {
+        let inside = DVec3::new(100.0, 5.0, 0.0);
+        assert_eq!(hit_direction(inside, inside, CENTER, RADIUS), Some(Vec3::Y));
+        assert_eq!(
+            hit_direction(DVec3::ZERO, DVec3::ZERO, CENTER, RADIUS),
+            None
+        );
+    }
+}
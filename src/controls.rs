@@ -0,0 +1,230 @@
+use bevy::{
+    input::gamepad::{GamepadConnection, GamepadConnectionEvent, GamepadSettings},
+    prelude::*,
+    utils::HashSet,
+};
+use serde::Deserialize;
+
+use crate::{
+    bindings::{Action, BindingScreen, Bindings},
+    camera::{find_target, CameraFocus, NamedTargets},
+    catalog::{BodyCatalog, BodyCatalogHandle},
+    clock::DateInput,
+    constant,
+};
+
+/// what the player asks for this frame, the same whether it comes from the keyboard or a gamepad
+#[derive(Resource, Default)]
+pub struct Actions {
+    /// -1 to 1, nose up
+    pub pitch: f32,
+    /// -1 to 1, nose left
+    pub yaw: f32,
+    /// -1 to 1, right wing down
+    pub roll: f32,
+    /// -1 to 1, opening the throttle
+    pub throttle: f32,
+    just_pressed: HashSet<Action>,
+}
+
+impl Actions {
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+}
+
+/// gamepad settings, read next to the key bindings
+const CONTROLLER_PATH: &str = "controller.ron";
+
+/// editable in the inspector, the deadzones are handed to bevy's `GamepadSettings`
+#[derive(Resource, Reflect, Deserialize)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct ControllerSettings {
+    /// stick deflection ignored around the center, 0 to 1
+    pub stick_deadzone: f32,
+    /// trigger travel ignored before it counts, 0 to 1
+    pub trigger_deadzone: f32,
+    /// pushing the stick forward pitches up unless inverted
+    pub invert_pitch: bool,
+}
+
+impl Default for ControllerSettings {
+    fn default() -> Self {
+        Self {
+            stick_deadzone: 0.15,
+            trigger_deadzone: 0.05,
+            invert_pitch: false,
+        }
+    }
+}
+
+pub fn load_controller_settings(mut commands: Commands) {
+    let settings = match std::fs::read_to_string(CONTROLLER_PATH) {
+        Ok(text) => ron::de::from_str(&text).unwrap_or_else(|error| {
+            warn!(
+                "ignoring controller settings in {}: {}",
+                CONTROLLER_PATH, error
+            );
+            ControllerSettings::default()
+        }),
+        Err(_) => ControllerSettings::default(),
+    };
+    commands.insert_resource(settings);
+}
+
+/// bevy already filters the gamepad axes, so the deadzones go there instead of on top
+pub fn apply_controller_settings(
+    settings: Res<ControllerSettings>,
+    mut gamepad_settings: ResMut<GamepadSettings>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    let stick = settings.stick_deadzone.clamp(0.0, 1.0);
+    let axis = &mut gamepad_settings.default_axis_settings;
+    axis.set_deadzone_upperbound(stick);
+    axis.set_deadzone_lowerbound(-stick);
+    gamepad_settings.default_button_axis_settings.low = settings.trigger_deadzone.clamp(0.0, 1.0);
+}
+
+/// the gamepad flying the airplane, the first one connected
+#[derive(Resource, Default)]
+pub struct ActiveGamepad(pub Option<Gamepad>);
+
+/// picks up a gamepad plugged in while none is in use, and falls back to another on unplugging
+pub fn handle_gamepad_connections(
+    mut events: EventReader<GamepadConnectionEvent>,
+    gamepads: Res<Gamepads>,
+    mut active: ResMut<ActiveGamepad>,
+) {
+    for event in events.read() {
+        match &event.connection {
+            GamepadConnection::Connected(info) => {
+                info!("gamepad {} connected", info.name);
+                if active.0.is_none() {
+                    active.0 = Some(event.gamepad);
+                }
+            }
+            GamepadConnection::Disconnected => {
+                info!("gamepad {} disconnected", event.gamepad.id);
+                if active.0 == Some(event.gamepad) {
+                    active.0 = gamepads.iter().find(|gamepad| *gamepad != event.gamepad);
+                }
+            }
+        }
+    }
+}
+
+/// keys come from `Bindings`, and do nothing while a date is typed or keys are rebound
+pub fn read_key_actions(
+    keys: Res<Input<KeyCode>>,
+    bindings: Res<Bindings>,
+    screen: Res<BindingScreen>,
+    date_input: Res<DateInput>,
+    mut actions: ResMut<Actions>,
+) {
+    *actions = Actions::default();
+    if screen.open || date_input.is_editing() {
+        return;
+    }
+    let pressed = |action| keys.any_pressed(bindings.keys(action).iter().copied());
+    let axis = |positive, negative| pressed(positive) as i8 as f32 - pressed(negative) as i8 as f32;
+    actions.pitch = axis(Action::PitchUp, Action::PitchDown);
+    actions.yaw = axis(Action::YawLeft, Action::YawRight);
+    actions.roll = axis(Action::RollRight, Action::RollLeft);
+    actions.throttle = axis(Action::ThrottleUp, Action::ThrottleDown);
+    actions.just_pressed = Action::ALL
+        .into_iter()
+        .filter(|action| keys.any_just_pressed(bindings.keys(*action).iter().copied()))
+        .collect();
+}
+
+/// left stick pitch and roll, right stick yaw, right and left triggers throttle,
+/// south button fires, shoulder buttons cycle the focus
+pub fn read_gamepad_actions(
+    axes: Res<Axis<GamepadAxis>>,
+    button_axes: Res<Axis<GamepadButton>>,
+    buttons: Res<Input<GamepadButton>>,
+    active: Res<ActiveGamepad>,
+    settings: Res<ControllerSettings>,
+    mut actions: ResMut<Actions>,
+) {
+    let Some(gamepad) = active.0 else {
+        return;
+    };
+    // deadzones are already applied, see `apply_controller_settings`
+    let stick = |axis| axes.get(GamepadAxis::new(gamepad, axis)).unwrap_or(0.0);
+    let trigger = |button| {
+        button_axes
+            .get(GamepadButton::new(gamepad, button))
+            .unwrap_or(0.0)
+    };
+    let button = |button| GamepadButton::new(gamepad, button);
+
+    let pitch = stick(GamepadAxisType::LeftStickY);
+    actions.pitch += if settings.invert_pitch { -pitch } else { pitch };
+    actions.roll += stick(GamepadAxisType::LeftStickX);
+    actions.yaw -= stick(GamepadAxisType::RightStickX);
+    actions.throttle +=
+        trigger(GamepadButtonType::RightTrigger2) - trigger(GamepadButtonType::LeftTrigger2);
+    let actions = actions.as_mut();
+    for (gamepad_button, action) in [
+        (GamepadButtonType::South, Action::Fire),
+        (GamepadButtonType::RightTrigger, Action::FocusNext),
+        (GamepadButtonType::LeftTrigger, Action::FocusPrevious),
+    ] {
+        if buttons.just_pressed(button(gamepad_button)) {
+            actions.just_pressed.insert(action);
+        }
+    }
+
+    for value in [
+        &mut actions.pitch,
+        &mut actions.yaw,
+        &mut actions.roll,
+        &mut actions.throttle,
+    ] {
+        *value = value.clamp(-1.0, 1.0);
+    }
+}
+
+/// steps through the bodies in catalog order, then the airplane, like the view buttons
+pub fn cycle_focus(
+    actions: Res<Actions>,
+    catalog_handle: Res<BodyCatalogHandle>,
+    catalogs: Res<Assets<BodyCatalog>>,
+    mut camera_focus: ResMut<CameraFocus>,
+    targets: NamedTargets,
+) {
+    let step: isize = match (
+        actions.just_pressed(Action::FocusNext),
+        actions.just_pressed(Action::FocusPrevious),
+    ) {
+        (true, false) => 1,
+        (false, true) => -1,
+        _ => return,
+    };
+    let Some(catalog) = catalogs.get(&catalog_handle.0) else {
+        return;
+    };
+    let order: Vec<Entity> = catalog
+        .bodies
+        .iter()
+        .map(|body| body.name.as_str())
+        .chain([constant::airplane::NAME])
+        .filter_map(|name| find_target(&targets, name))
+        .collect();
+    if order.is_empty() {
+        return;
+    }
+    let next = match camera_focus
+        .focus
+        .and_then(|focus| order.iter().position(|entity| *entity == focus))
+    {
+        Some(index) => (index as isize + step).rem_euclid(order.len() as isize) as usize,
+        None if step > 0 => 0,
+        None => order.len() - 1,
+    };
+    camera_focus.focus = Some(order[next]);
+}
@@ -0,0 +1,71 @@
+use bevy::{asset::LoadState, prelude::*};
+
+use crate::{
+    catalog::{BodyCatalog, BodyCatalogHandle},
+    constant::PLANET_GLTF_SCALE,
+    planets::CelestialBody,
+};
+
+/// a body whose GLB model failed to load is drawn as a plain sphere in its catalog color
+pub fn replace_missing_models(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    catalog_handle: Res<BodyCatalogHandle>,
+    catalogs: Res<Assets<BodyCatalog>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    bodies: Query<(Entity, &Name, &Handle<Scene>), With<CelestialBody>>,
+) {
+    let Some(catalog) = catalogs.get(&catalog_handle.0) else {
+        return;
+    };
+    for (entity, name, scene) in &bodies {
+        if asset_server.get_load_state(scene) != Some(LoadState::Failed) {
+            continue;
+        }
+        let Some(body) = catalog
+            .bodies
+            .iter()
+            .find(|body| body.name == name.as_str())
+        else {
+            continue;
+        };
+        warn!(
+            "could not load model {} of {}, drawing a plain sphere instead",
+            body.model, body.name
+        );
+
+        let color = body.color();
+        // the light sits inside the Sun, which has to glow by itself
+        let material = if body.orbit.is_none() {
+            StandardMaterial {
+                base_color: color,
+                emissive: color,
+                unlit: true,
+                ..default()
+            }
+        } else {
+            StandardMaterial {
+                base_color: color,
+                perceptual_roughness: 0.9,
+                ..default()
+            }
+        };
+        // same size as the GLB models, so the body's scale applies unchanged
+        let sphere = shape::UVSphere {
+            radius: 1.0 / PLANET_GLTF_SCALE,
+            sectors: 64,
+            stacks: 32,
+        };
+        commands
+            .entity(entity)
+            .remove::<Handle<Scene>>()
+            .with_children(|parent| {
+                parent.spawn(PbrBundle {
+                    mesh: meshes.add(sphere.into()),
+                    material: materials.add(material),
+                    ..default()
+                });
+            });
+    }
+}
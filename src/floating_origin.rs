@@ -0,0 +1,40 @@
+use bevy::{math::DVec3, prelude::*};
+
+use crate::scale::ScaledPosition;
+
+/// position in the simulation, in f64 so that it stays exact anywhere in the solar system;
+/// the render `Transform` is derived from it by `apply_floating_origin`
+#[derive(Component, Default, Clone, Copy)]
+pub struct WorldPosition(pub DVec3);
+
+/// where the entity was when the simulation clock started this frame's step,
+/// so collisions can test the whole path covered in between
+#[derive(Component, Default, Clone, Copy)]
+pub struct PreviousPosition(pub DVec3);
+
+/// position rendered at `Vec3::ZERO`, kept under the camera focus by `control_camera`
+/// so everything near the camera is close to the origin, where f32 is precise
+#[derive(Resource, Default)]
+pub struct FloatingOrigin(pub DVec3);
+
+impl FloatingOrigin {
+    pub fn to_render(&self, world: DVec3) -> Vec3 {
+        (world - self.0).as_vec3()
+    }
+
+    pub fn to_world(&self, render: Vec3) -> DVec3 {
+        self.0 + render.as_dvec3()
+    }
+}
+
+/// re-centre every render `Transform` around the floating origin,
+/// bodies are drawn where the scale mode puts them
+pub fn apply_floating_origin(
+    origin: Res<FloatingOrigin>,
+    mut query: Query<(&mut Transform, &WorldPosition, Option<&ScaledPosition>)>,
+) {
+    for (mut transform, position, scaled) in &mut query {
+        let position = scaled.map_or(position.0, |scaled| scaled.0);
+        transform.translation = origin.to_render(position);
+    }
+}
@@ -0,0 +1,76 @@
+use bevy::{math::DVec3, prelude::*};
+
+use crate::{
+    clock::SimulationClock,
+    floating_origin::{PreviousPosition, WorldPosition},
+};
+
+/// km, keeps the pull finite when passing through a body's center
+const MIN_DISTANCE: f64 = 1.0;
+/// simulated seconds per integration step, fast time rates take several steps a frame
+const MAX_STEP: f64 = 10.0;
+/// per frame, beyond that the steps get longer instead
+const MAX_STEPS: usize = 1000;
+
+/// gravitational parameter of a body, km³/s²
+#[derive(Component, Clone, Copy)]
+pub struct Gravity(pub f64);
+
+/// velocity of a free flying entity like the airplane or a bullet, km/s
+#[derive(Component, Default, Clone, Copy)]
+pub struct Velocity(pub DVec3);
+
+/// acceleration of a craft under its own power, km/s²
+#[derive(Component, Default, Clone, Copy)]
+pub struct Thrust(pub DVec3);
+
+/// sum of the pulls of every body at a position, km/s²
+pub fn acceleration_at(position: DVec3, bodies: impl IntoIterator<Item = (DVec3, f64)>) -> DVec3 {
+    bodies
+        .into_iter()
+        .map(|(body, gm)| {
+            let offset = body - position;
+            let distance = offset.length().max(MIN_DISTANCE);
+            offset * (gm / (distance * distance * distance))
+        })
+        .sum()
+}
+
+type Craft<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut WorldPosition,
+        &'static mut PreviousPosition,
+        &'static mut Velocity,
+        Option<&'static Thrust>,
+    ),
+    Without<Gravity>,
+>;
+
+/// moves everything flying freely on the simulation clock, like the bodies, so pulls and
+/// orbits do not depend on the time rate; the bodies are interpolated between their
+/// previous and current positions during the steps of a frame
+pub fn move_craft(
+    clock: Res<SimulationClock>,
+    bodies: Query<(&WorldPosition, &PreviousPosition, &Gravity)>,
+    mut craft: Craft,
+) {
+    let dt = clock.delta_seconds;
+    let steps = ((dt.abs() / MAX_STEP).ceil() as usize).clamp(1, MAX_STEPS);
+    let step = dt / steps as f64;
+    for (mut position, mut previous, mut velocity, thrust) in &mut craft {
+        previous.0 = position.0;
+        let thrust = thrust.map_or(DVec3::ZERO, |thrust| thrust.0);
+        for i in 1..=steps {
+            let along = i as f64 / steps as f64;
+            let pulls = bodies
+                .iter()
+                .map(|(body, before, gravity)| (before.0.lerp(body.0, along), gravity.0));
+            // velocity first, then position, which keeps the integration symplectic so
+            // orbits do not spiral in or out
+            velocity.0 += (acceleration_at(position.0, pulls) + thrust) * step;
+            position.0 += velocity.0 * step;
+        }
+    }
+}
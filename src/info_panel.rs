@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+
+use crate::{
+    camera::CameraFocus,
+    clock::SimulationClock,
+    constant::{AU, ONE_DAY_SECONDS, SPACE_SCALE},
+    floating_origin::WorldPosition,
+    orbit::world_to_ecliptic,
+    planets::{CelestialBody, Earth, Planets},
+};
+
+const SPEED_OF_LIGHT: f64 = 299_792.458; // km/s
+
+#[derive(Component)]
+pub struct InfoPanel;
+
+#[derive(Component)]
+pub struct InfoText;
+
+pub fn setup_info_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    right: Val::Px(10.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    display: Display::None,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                ..default()
+            },
+            InfoPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 18.0,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                        ..default()
+                    },
+                ),
+                InfoText,
+            ));
+        });
+}
+
+pub fn update_info_panel(
+    camera_focus: Res<CameraFocus>,
+    clock: Res<SimulationClock>,
+    bodies: Query<(&CelestialBody, &WorldPosition, &Transform)>,
+    earth: Query<&WorldPosition, With<Earth>>,
+    camera: Query<&Transform, With<Camera>>,
+    mut panel: Query<&mut Style, With<InfoPanel>>,
+    mut text: Query<&mut Text, With<InfoText>>,
+) {
+    let Ok(mut style) = panel.get_single_mut() else {
+        return;
+    };
+    let Some((body, world, transform)) =
+        camera_focus.focus.and_then(|focus| bodies.get(focus).ok())
+    else {
+        style.display = Display::None;
+        return;
+    };
+    style.display = Display::Flex;
+
+    let position = world.0 / SPACE_SCALE as f64;
+    let ecliptic = world_to_ecliptic(position) / AU;
+    let mut lines = vec![
+        body.name().to_string(),
+        format!("Radius: {:.1} km", body.radius()),
+        format!("Distance to Sun: {}", format_distance(position.length())),
+    ];
+    if let Some(semi_major_axis) = body.semi_major_axis() {
+        lines.push(format!(
+            "Semi-major axis: {}",
+            format_distance(semi_major_axis)
+        ));
+    }
+    if let Some(orbital_period) = body.orbital_period() {
+        lines.push(format!("Orbital period: {}", format_period(orbital_period)));
+    }
+    lines.push(format!(
+        "Axial tilt: {:.2}°",
+        body.axial_tilt().to_degrees()
+    ));
+    lines.push(format!(
+        "Rotation period: {}{}",
+        format_period(body.rotation_period().abs()),
+        if body.rotation_period() < 0.0 {
+            " (retrograde)"
+        } else {
+            ""
+        }
+    ));
+    if let Some(orbit) = body.orbit() {
+        let speed = orbit.velocity_at(clock.seconds_since_j2000()).length();
+        lines.push(format!("Orbital speed: {speed:.2} km/s"));
+    }
+    lines.push(format!(
+        "Heliocentric: x {:.4} y {:.4} z {:.4} AU",
+        ecliptic.x, ecliptic.y, ecliptic.z
+    ));
+    if let Ok(camera) = camera.get_single() {
+        let distance = camera.translation.distance(transform.translation) / SPACE_SCALE;
+        lines.push(format!(
+            "Distance from camera: {}",
+            format_distance(distance as f64)
+        ));
+    }
+    if let Ok(earth) = earth.get_single() {
+        let distance = earth.0.distance(world.0) / SPACE_SCALE as f64;
+        if distance > 0.0 {
+            let seconds = distance / SPEED_OF_LIGHT;
+            lines.push(format!(
+                "Light time to Earth: {}m {:02}s",
+                (seconds / 60.0) as u32,
+                (seconds % 60.0) as u32
+            ));
+        }
+    }
+
+    for mut text in &mut text {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+fn format_distance(km: f64) -> String {
+    if km >= 0.01 * AU {
+        format!("{:.4} AU", km / AU)
+    } else {
+        format!("{km:.0} km")
+    }
+}
+
+fn format_period(seconds: f32) -> String {
+    let days = seconds / ONE_DAY_SECONDS;
+    if days >= 2.0 * 365.25 {
+        format!("{:.2} years", days / 365.25)
+    } else if days >= 2.0 {
+        format!("{days:.2} days")
+    } else {
+        format!("{:.2} hours", days * 24.0)
+    }
+}
@@ -0,0 +1,175 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    camera::CameraFocus,
+    overlays::Overlays,
+    planets::{CelestialBody, OrbitsParent, Planets, Star},
+};
+
+/// labels closer than this to their parent (or the Sun) on screen are hidden
+const FADE_MIN_PIXELS: f32 = 12.0;
+/// and fully visible from this far on
+const FADE_FULL_PIXELS: f32 = 48.0;
+/// gap between a body and its label
+const LABEL_OFFSET: Vec2 = Vec2::new(8.0, 0.0);
+/// tries moving a label below the one it overlaps before hiding it
+const PLACEMENT_TRIES: usize = 3;
+
+/// screen-space name of a body
+#[derive(Component)]
+pub struct BodyLabel(pub Entity);
+
+/// also drops the labels of bodies despawned by a catalog reload
+pub fn spawn_labels(
+    mut commands: Commands,
+    bodies: Query<(Entity, &CelestialBody), Added<CelestialBody>>,
+    mut removed: RemovedComponents<CelestialBody>,
+    labels: Query<(Entity, &BodyLabel)>,
+) {
+    let removed: Vec<Entity> = removed.read().collect();
+    if !removed.is_empty() {
+        for (label, body) in &labels {
+            if removed.contains(&body.0) {
+                commands.entity(label).despawn_recursive();
+            }
+        }
+    }
+    for (entity, body) in &bodies {
+        commands.spawn((
+            TextBundle {
+                visibility: Visibility::Hidden,
+                ..TextBundle::from_section(
+                    body.name(),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    ..default()
+                })
+            },
+            BodyLabel(entity),
+        ));
+    }
+}
+
+// hidden labels keep their layout, so their size is known when placing them
+type Labels<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static BodyLabel,
+        &'static Node,
+        &'static mut Style,
+        &'static mut Visibility,
+        &'static mut Text,
+    ),
+>;
+
+type LabelledBodies<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        &'static CelestialBody,
+        Option<&'static OrbitsParent>,
+        Has<Star>,
+    ),
+>;
+
+/// labels can be turned off, see `toggle_overlays`
+pub fn update_labels(
+    overlays: Res<Overlays>,
+    camera_focus: Res<CameraFocus>,
+    cameras: Query<(&Camera, &Transform)>,
+    bodies: LabelledBodies,
+    mut labels: Labels,
+) {
+    let Ok((camera, camera_transform)) = cameras.get_single() else {
+        return;
+    };
+    if !overlays.labels {
+        for (.., mut visibility, _) in &mut labels {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    }
+    // this frame's camera, its global transform is only propagated after update
+    let camera_transform = GlobalTransform::from(*camera_transform);
+    let screen: HashMap<Entity, Vec2> = bodies
+        .iter()
+        .filter_map(|(entity, transform, ..)| {
+            camera
+                .world_to_viewport(&camera_transform, transform.translation)
+                .map(|position| (entity, position))
+        })
+        .collect();
+    let sun = bodies
+        .iter()
+        .find(|(.., star)| *star)
+        .and_then(|(entity, ..)| screen.get(&entity));
+
+    // the focused body first, then stars, planets and moons, largest first
+    let mut order: Vec<(Entity, (bool, bool, bool, f32))> = labels
+        .iter()
+        .filter_map(|(label, target, ..)| {
+            let (entity, _, body, parent, star) = bodies.get(target.0).ok()?;
+            let focused = camera_focus.focus == Some(entity);
+            Some((label, (!focused, !star, parent.is_some(), -body.radius())))
+        })
+        .collect();
+    order.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut placed: Vec<Rect> = Vec::new();
+    for (label, _) in order {
+        let Ok((_, target, node, mut style, mut visibility, mut text)) = labels.get_mut(label)
+        else {
+            continue;
+        };
+        let (Ok((entity, _, _, parent, star)), Some(position)) =
+            (bodies.get(target.0), screen.get(&target.0))
+        else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        let anchor = match parent {
+            Some(parent) => screen.get(&parent.0),
+            None if !star => sun,
+            None => None,
+        };
+        let alpha = match anchor {
+            Some(anchor) if camera_focus.focus != Some(entity) => ((position.distance(*anchor)
+                - FADE_MIN_PIXELS)
+                / (FADE_FULL_PIXELS - FADE_MIN_PIXELS))
+                .clamp(0.0, 1.0),
+            _ => 1.0,
+        };
+
+        let size = node.size();
+        let mut corner = *position + LABEL_OFFSET - Vec2::new(0.0, size.y / 2.0);
+        let mut rect = Rect::from_corners(corner, corner + size);
+        let mut tries = 0;
+        while tries < PLACEMENT_TRIES
+            && placed.iter().any(|other| !other.intersect(rect).is_empty())
+        {
+            corner.y += size.y;
+            rect = Rect::from_corners(corner, corner + size);
+            tries += 1;
+        }
+        if alpha <= 0.0 || tries == PLACEMENT_TRIES {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        placed.push(rect);
+        *visibility = Visibility::Inherited;
+        style.left = Val::Px(corner.x);
+        style.top = Val::Px(corner.y);
+        text.sections[0].style.color.set_a(alpha);
+    }
+}
@@ -0,0 +1,72 @@
+use std::f32::consts::PI;
+
+use bevy::{pbr::NotShadowCaster, prelude::*};
+
+use crate::{
+    bindings::Action,
+    constant::{AU, SPACE_SCALE},
+    controls::Actions,
+    planets::{CelestialBody, Planets, Star},
+};
+
+/// brightness of a surface facing the Sun at 1 AU, falling off with the inverse square law
+const ILLUMINANCE_AT_1AU: f32 = 20.0;
+/// lumens needed to reach `ILLUMINANCE_AT_1AU`
+const SUN_LIGHT_INTENSITY: f32 =
+    ILLUMINANCE_AT_1AU * 4.0 * PI * (AU as f32 * SPACE_SCALE) * (AU as f32 * SPACE_SCALE);
+/// past Neptune, so every planet is lit
+const SUN_LIGHT_RANGE: f32 = 50.0 * AU as f32 * SPACE_SCALE;
+/// only enough to make out night sides and crescents
+pub const AMBIENT_BRIGHTNESS: f32 = 0.05;
+
+#[derive(Component)]
+pub struct SunLight;
+
+/// every star shines with a point light at its center
+pub fn attach_sun_light(
+    mut commands: Commands,
+    stars: Query<(Entity, &CelestialBody), Added<Star>>,
+) {
+    for (entity, star) in &stars {
+        commands.entity(entity).with_children(|parent| {
+            parent.spawn((
+                PointLightBundle {
+                    point_light: PointLight {
+                        intensity: SUN_LIGHT_INTENSITY,
+                        range: SUN_LIGHT_RANGE,
+                        radius: star.radius() * SPACE_SCALE,
+                        shadows_enabled: false,
+                        ..default()
+                    },
+                    ..default()
+                },
+                SunLight,
+            ));
+        });
+    }
+}
+
+/// the light sits inside the star's model, which must not block it
+pub fn stars_cast_no_shadow(
+    mut commands: Commands,
+    stars: Query<Entity, With<Star>>,
+    children: Query<&Children>,
+    meshes: Query<(), (With<Handle<Mesh>>, Without<NotShadowCaster>)>,
+) {
+    for star in &stars {
+        for descendant in children.iter_descendants(star) {
+            if meshes.contains(descendant) {
+                commands.entity(descendant).insert(NotShadowCaster);
+            }
+        }
+    }
+}
+
+/// shadows can be turned on to watch eclipses
+pub fn toggle_shadows(actions: Res<Actions>, mut lights: Query<&mut PointLight, With<SunLight>>) {
+    if actions.just_pressed(Action::ToggleShadows) {
+        for mut light in &mut lights {
+            light.shadows_enabled = !light.shadows_enabled;
+        }
+    }
+}
@@ -1,40 +1,75 @@
 pub mod airplane;
+pub mod bindings;
 pub mod button;
 pub mod camera;
+pub mod catalog;
+pub mod clock;
+pub mod collision;
 pub mod constant;
+pub mod controls;
+pub mod fallback;
+pub mod floating_origin;
+pub mod gravity;
+pub mod info_panel;
+pub mod labels;
+pub mod lighting;
+pub mod orbit;
+pub mod overlays;
+pub mod picking;
 pub mod planets;
+pub mod rings;
+pub mod scale;
+pub mod starfield;
+pub mod target_practice;
+pub mod trail;
 
-use std::sync::Arc;
-
-use crate::constant::PLANET_GLTF_SCALE;
-use crate::planets::CommonPlanets;
-use crate::planets::Planets;
 use airplane::{
-    airplane_direction, control_airplane, control_bullet, set_plane, spawn_bullet, Airplane,
+    control_airplane, control_bullet, reset_airplane, set_plane, spawn_bullet, steer_airplane,
+    update_flying,
 };
-use bevy::asset::StrongHandle;
-use bevy::scene::SceneInstance;
-use bevy::{prelude::*, window::WindowMode};
+use bevy::{input::InputSystem, math::DVec3, prelude::*, window::WindowMode};
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
-use bevy_inspector_egui::DefaultInspectorConfigPlugin;
-use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraPlugin};
+use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraPlugin, PanOrbitCameraSystemSet};
+use bindings::{
+    capture_binding, click_binding_row, load_bindings, setup_binding_screen, toggle_binding_screen,
+    update_binding_rows, BindingScreen,
+};
 use button::{
-    handle_button, mouse_button_input, scroll_events, touchpad_gestures, ChangeViewButton,
+    handle_button, mouse_button_input, scroll_events, setup_view_buttons, touchpad_gestures,
 };
-use camera::control_camera;
-use constant::earth;
-use constant::{moon::DISTANCE_TO_EARTH, SPACE_SCALE};
-use planets::{move_planets, Earth};
-
-#[derive(Component)]
-pub struct Moon {
-    distance_to_earth: f32,
-}
-
-#[derive(Resource)]
-pub struct CameraFocus {
-    focus: String,
-}
+use camera::{control_camera, CameraFocus, CameraSettings};
+use catalog::{spawn_bodies, BodyCatalog, BodyCatalogHandle, BodyCatalogLoader, CATALOG_PATH};
+use clock::{
+    advance_clock, handle_time_control_button, jump_to_date, setup_clock, time_control_keys,
+    update_clock_text, DateInput, SimulationClock, TimeScale,
+};
+use collision::{detect_collisions, draw_impacts, spawn_impacts, AirplaneCrashed, BulletHit};
+use constant::{AU, SPACE_SCALE};
+use controls::{
+    apply_controller_settings, cycle_focus, handle_gamepad_connections, load_controller_settings,
+    read_gamepad_actions, read_key_actions, Actions, ActiveGamepad, ControllerSettings,
+};
+use fallback::replace_missing_models;
+use floating_origin::{apply_floating_origin, FloatingOrigin};
+use gravity::move_craft;
+use info_panel::{setup_info_panel, update_info_panel};
+use labels::{spawn_labels, update_labels};
+use lighting::{attach_sun_light, stars_cast_no_shadow, toggle_shadows, AMBIENT_BRIGHTNESS};
+use overlays::{draw_ecliptic_grid, draw_orbits, draw_rotation_axes, toggle_overlays, Overlays};
+use picking::{click_to_focus, highlight_hovered, pick_bodies, Hovered};
+use planets::{follow_orbit_parents, move_planets};
+use rings::attach_rings;
+use scale::{cycle_scale_mode, scale_bodies, setup_scale_mode_text, DisplayScale};
+use starfield::{
+    build_constellations, center_starfield, draw_constellations, setup_starfield, spawn_starfield,
+    update_constellation_labels, Constellations, ConstellationsLoader, StarCatalog,
+    StarCatalogLoader, SKY_RADIUS,
+};
+use target_practice::{
+    control_target_practice, hit_targets, move_targets, setup_target_practice, spawn_targets,
+    update_target_practice_text,
+};
+use trail::{draw_trails, record_trails};
 
 fn main() {
     let window_plugin = WindowPlugin {
@@ -50,31 +85,140 @@ fn main() {
         .add_plugins(DefaultPlugins.set(window_plugin))
         .add_plugins(WorldInspectorPlugin::new())
         .add_plugins(PanOrbitCameraPlugin)
-        .insert_resource(ClearColor(Color::rgb(0.1, 0.0, 0.15)))
+        .init_asset::<BodyCatalog>()
+        .init_asset_loader::<BodyCatalogLoader>()
+        .init_asset::<StarCatalog>()
+        .init_asset_loader::<StarCatalogLoader>()
+        .init_asset::<Constellations>()
+        .init_asset_loader::<ConstellationsLoader>()
+        .insert_resource(ClearColor(Color::BLACK))
         .insert_resource(AmbientLight {
             color: Color::default(),
-            brightness: 0.75,
-        })
-        .insert_resource(CameraFocus {
-            focus: ChangeViewButton::Global.name().into(),
+            brightness: AMBIENT_BRIGHTNESS,
         })
-        .add_systems(Startup, (setup, button::setup_button, set_plane))
+        .insert_resource(SimulationClock::now())
+        .init_resource::<DateInput>()
+        .init_resource::<TimeScale>()
+        .init_resource::<CameraFocus>()
+        .init_resource::<CameraSettings>()
+        .init_resource::<Hovered>()
+        .init_resource::<FloatingOrigin>()
+        .init_resource::<DisplayScale>()
+        .init_resource::<Overlays>()
+        .init_resource::<Actions>()
+        .register_type::<ControllerSettings>()
+        .init_resource::<ActiveGamepad>()
+        .init_resource::<BindingScreen>()
+        .add_event::<BulletHit>()
+        .add_event::<AirplaneCrashed>()
+        .add_systems(
+            Startup,
+            (
+                setup,
+                setup_clock,
+                setup_info_panel,
+                setup_scale_mode_text,
+                setup_starfield,
+                setup_target_practice,
+                load_bindings,
+                load_controller_settings,
+                setup_binding_screen,
+            ),
+        )
+        .add_systems(PreUpdate, (update_flying, advance_clock).chain())
+        .add_systems(PreUpdate, apply_controller_settings.before(InputSystem))
+        .add_systems(
+            PreUpdate,
+            (
+                handle_gamepad_connections,
+                read_key_actions,
+                read_gamepad_actions,
+            )
+                .chain()
+                .after(InputSystem),
+        )
         .add_systems(
             Update,
             (
-                move_moon,
-                axis,
-                handle_button,
-                control_camera,
+                (
+                    spawn_bodies,
+                    replace_missing_models,
+                    attach_rings,
+                    spawn_labels,
+                    spawn_starfield,
+                    build_constellations,
+                ),
+                setup_view_buttons,
+                (
+                    (
+                        move_planets,
+                        follow_orbit_parents,
+                        cycle_scale_mode,
+                        scale_bodies,
+                    )
+                        .chain(),
+                    (
+                        (set_plane, steer_airplane, control_airplane).chain(),
+                        (move_craft.after(follow_orbit_parents), move_targets),
+                        control_bullet,
+                        hit_targets,
+                        detect_collisions,
+                        (spawn_impacts, reset_airplane),
+                    )
+                        .chain(),
+                    handle_button,
+                    cycle_focus,
+                )
+                    .before(control_camera),
+                record_trails.after(scale_bodies).after(move_craft),
+                // simulation in f64 world space above, render space from here on
+                (
+                    control_camera,
+                    apply_floating_origin,
+                    (
+                        axis,
+                        draw_orbits,
+                        draw_ecliptic_grid,
+                        draw_rotation_axes,
+                        draw_trails,
+                        draw_impacts,
+                    ),
+                )
+                    .chain()
+                    .before(PanOrbitCameraSystemSet),
                 mouse_button_input,
+                (pick_bodies, (highlight_hovered, click_to_focus)).chain(),
                 touchpad_gestures,
                 scroll_events,
-                control_airplane,
-                airplane_direction,
-                move_planets::<CommonPlanets>,
-                move_planets::<Earth>,
                 spawn_bullet,
-                control_bullet,
+                jump_to_date,
+                update_clock_text,
+                time_control_keys,
+                handle_time_control_button,
+                (update_info_panel, update_labels)
+                    .after(apply_floating_origin)
+                    .after(PanOrbitCameraSystemSet),
+                (
+                    center_starfield,
+                    draw_constellations,
+                    update_constellation_labels,
+                )
+                    .after(PanOrbitCameraSystemSet),
+                (attach_sun_light, stars_cast_no_shadow, toggle_shadows),
+                toggle_overlays,
+                (
+                    toggle_binding_screen,
+                    click_binding_row,
+                    capture_binding,
+                    update_binding_rows,
+                )
+                    .chain(),
+                (
+                    control_target_practice,
+                    spawn_targets,
+                    update_target_practice_text,
+                )
+                    .chain(),
             ),
         )
         .run()
@@ -85,279 +229,18 @@ fn main() {
     // }
 }
 
-fn move_moon(
-    time: Res<Time>,
-    mut gizmos: Gizmos,
-    mut query: Query<(&mut Transform, &Moon)>,
-    earth_query: Query<(&Transform, &Earth), Without<Moon>>,
-) {
-    let earth = earth_query.single();
-    // moon orbit
-    gizmos
-        .circle(
-            earth.0.translation,
-            Vec3::Y,
-            constant::moon::DISTANCE_TO_EARTH * SPACE_SCALE,
-            Color::GRAY,
-        )
-        .segments(128);
-
-    for (mut transform, moon) in &mut query {
-        // let movement = transf.forward() + transf.left();
-        transform.rotate_y(
-            constant::TIME_SCALE * constant::moon::ROTATION_VELCITY * time.delta_seconds(),
-        );
-
-        let elapsed_seconds = time.elapsed_seconds();
-        let angle = elapsed_seconds * constant::TIME_SCALE * constant::moon::ORBITAL_VELCITY;
-        transform.translation = earth.0.translation
-            + Vec3::new(
-                moon.distance_to_earth * angle.cos(),
-                0.0,
-                moon.distance_to_earth * angle.sin(),
-            );
-    }
-}
-
 fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
-    // commands
-    //     .spawn(PointLightBundle {
-    //         point_light: PointLight {
-    //             intensity: 9000.0,
-    //             // range: 300.,
-    //             // radius: 60.0,
-    //             shadows_enabled: true,
-    //             ..default()
-    //         },
-    //         transform: Transform::from_xyz(0.0, 0.0, 0.0),
-    //         ..default()
-    //     })
-    //     .with_children(|builder| {
-    //         let scene = asset_server.load("sun.glb#Scene0");
-    //         builder.spawn((SceneBundle {
-    //             scene: scene,
-    //             transform: Transform::from_xyz(0., 0., 0.).with_scale(Vec3::splat(50.)),
-    //             ..default()
-    //         },));
-    //     });
-
-    // Sun
-    commands.spawn((SceneBundle {
-        scene: asset_server.load("Sun.glb#Scene0"),
-        transform: Transform::from_xyz(0., 0., 0.).with_scale(Vec3::splat(
-            PLANET_GLTF_SCALE * SPACE_SCALE * constant::sun::RADIUS,
-        )),
-        ..default()
-    },));
-
-    // Mercury
-    commands.spawn((
-        SceneBundle {
-            scene: asset_server.load("Mercury.glb#Scene0"),
-            transform: Transform::from_xyz(
-                constant::mercury::DISTANCE_TO_SUN * SPACE_SCALE,
-                0.,
-                0.,
-            )
-            .with_scale(Vec3::splat(
-                PLANET_GLTF_SCALE * SPACE_SCALE * constant::mercury::RADIUS,
-            )),
-            ..default()
-        },
-        CommonPlanets::new(
-            constant::mercury::DISTANCE_TO_SUN * SPACE_SCALE,
-            constant::mercury::RADIUS,
-            constant::mercury::ROTATION_VELCITY,
-            constant::mercury::ORBITAL_VELCITY,
-            constant::mercury::NAME.into(),
-        ),
-    ));
-
-    // Venus
-    commands.spawn((
-        SceneBundle {
-            scene: asset_server.load("Venus.glb#Scene0"),
-            transform: Transform::from_xyz(constant::venus::DISTANCE_TO_SUN * SPACE_SCALE, 0., 0.)
-                .with_scale(Vec3::splat(
-                    PLANET_GLTF_SCALE * SPACE_SCALE * constant::venus::RADIUS,
-                )),
-            ..default()
-        },
-        CommonPlanets::new(
-            constant::venus::DISTANCE_TO_SUN * SPACE_SCALE,
-            constant::venus::RADIUS,
-            constant::venus::ROTATION_VELCITY,
-            constant::venus::ORBITAL_VELCITY,
-            constant::venus::NAME.into(),
-        ),
-    ));
-
-    // Earth
-    let earch_x = constant::earth::DISTANCE_TO_SUN * SPACE_SCALE;
-    commands.spawn((
-        SceneBundle {
-            scene: asset_server.load("Earth.glb#Scene0"),
-            transform: Transform::from_xyz(earch_x, 0., 0.).with_scale(Vec3::splat(
-                PLANET_GLTF_SCALE * SPACE_SCALE * constant::earth::RADIUS,
-            )),
-            ..default()
-        },
-        Earth::new(
-            constant::earth::DISTANCE_TO_SUN * SPACE_SCALE,
-            constant::earth::RADIUS,
-            constant::earth::ROTATION_VELCITY,
-            constant::earth::ORBITAL_VELCITY,
-            constant::earth::NAME.into(),
-        ),
-    ));
-    // Moon
-    commands.spawn((
-        // PbrBundle {
-        //     mesh: meshes.add(
-        //         shape::UVSphere {
-        //             radius: constant::Moon::RADIUS * constant::SPACE_SCALE,
-        //             ..default()
-        //         }
-        //         .into(),
-        //     ),
-        //     material: materials.add(Color::SILVER.into()),
-        //     transform: Transform::from_xyz(
-        //         constant::Moon::DISTANCE_TO_EARTH * constant::SPACE_SCALE,
-        //         0.0,
-        //         0.0,
-        //     ),
-        //     ..default()
-        // },
-        SceneBundle {
-            scene: asset_server.load("Moon.glb#Scene0"),
-            transform: Transform::from_xyz(
-                (DISTANCE_TO_EARTH + constant::earth::RADIUS) * SPACE_SCALE,
-                0.,
-                0.,
-            )
-            .with_scale(Vec3::splat(
-                PLANET_GLTF_SCALE * SPACE_SCALE * constant::moon::RADIUS,
-            )),
-            ..default()
-        },
-        Moon {
-            distance_to_earth: constant::moon::DISTANCE_TO_EARTH * SPACE_SCALE,
-        },
-    ));
-
-    // Mars
-    commands.spawn((
-        SceneBundle {
-            scene: asset_server.load("Mars.glb#Scene0"),
-            transform: Transform::from_xyz(constant::mars::DISTANCE_TO_SUN * SPACE_SCALE, 0., 0.)
-                .with_scale(Vec3::splat(
-                    PLANET_GLTF_SCALE * SPACE_SCALE * constant::mars::RADIUS,
-                )),
-            ..default()
-        },
-        CommonPlanets::new(
-            constant::mars::DISTANCE_TO_SUN * SPACE_SCALE,
-            constant::mars::RADIUS,
-            constant::mars::ROTATION_VELCITY,
-            constant::mars::ORBITAL_VELCITY,
-            constant::mars::NAME.into(),
-        ),
-    ));
-
-    // Jupiter
-    commands.spawn((
-        SceneBundle {
-            scene: asset_server.load("Jupiter.glb#Scene0"),
-            transform: Transform::from_xyz(
-                constant::jupiter::DISTANCE_TO_SUN * SPACE_SCALE,
-                0.,
-                0.,
-            )
-            .with_scale(Vec3::splat(
-                PLANET_GLTF_SCALE * SPACE_SCALE * constant::jupiter::RADIUS,
-            )),
-            ..default()
-        },
-        CommonPlanets::new(
-            constant::jupiter::DISTANCE_TO_SUN * SPACE_SCALE,
-            constant::jupiter::RADIUS,
-            constant::jupiter::ROTATION_VELCITY,
-            constant::jupiter::ORBITAL_VELCITY,
-            constant::jupiter::NAME.into(),
-        ),
-    ));
-
-    // Saturn
-    commands.spawn((
-        SceneBundle {
-            scene: asset_server.load("Saturn.glb#Scene0"),
-            transform: Transform::from_xyz(constant::saturn::DISTANCE_TO_SUN * SPACE_SCALE, 0., 0.)
-                .with_scale(Vec3::splat(
-                    PLANET_GLTF_SCALE * SPACE_SCALE * constant::saturn::RADIUS,
-                )),
-            ..default()
-        },
-        CommonPlanets::new(
-            constant::saturn::DISTANCE_TO_SUN * SPACE_SCALE,
-            constant::saturn::RADIUS,
-            constant::saturn::ROTATION_VELCITY,
-            constant::saturn::ORBITAL_VELCITY,
-            constant::saturn::NAME.into(),
-        ),
-    ));
-
-    // Uranus
-    commands.spawn((
-        SceneBundle {
-            scene: asset_server.load("Uranus.glb#Scene0"),
-            transform: Transform::from_xyz(constant::uranus::DISTANCE_TO_SUN * SPACE_SCALE, 0., 0.)
-                .with_scale(Vec3::splat(
-                    PLANET_GLTF_SCALE * SPACE_SCALE * constant::uranus::RADIUS,
-                )),
-            ..default()
-        },
-        // Uranus {
-        //     distance_to_sun: constant::uranus::DISTANCE_TO_SUN * SPACE_SCALE,
-        // }
-        CommonPlanets::new(
-            constant::uranus::DISTANCE_TO_SUN * SPACE_SCALE,
-            constant::uranus::RADIUS,
-            constant::uranus::ROTATION_VELCITY,
-            constant::uranus::ORBITAL_VELCITY,
-            constant::uranus::NAME.into(),
-        ),
-    ));
-
-    // Neptune
-    commands.spawn((
-        SceneBundle {
-            scene: asset_server.load("Neptune.glb#Scene0"),
-            transform: Transform::from_xyz(
-                constant::neptune::DISTANCE_TO_SUN * SPACE_SCALE,
-                0.,
-                0.,
-            )
-            .with_scale(Vec3::splat(
-                PLANET_GLTF_SCALE * SPACE_SCALE * constant::neptune::RADIUS,
-            )),
-            ..default()
-        },
-        CommonPlanets::new(
-            constant::neptune::DISTANCE_TO_SUN * SPACE_SCALE,
-            constant::neptune::RADIUS,
-            constant::neptune::ROTATION_VELCITY,
-            constant::neptune::ORBITAL_VELCITY,
-            constant::neptune::NAME.into(),
-        ),
-    ));
+    commands.insert_resource(BodyCatalogHandle(asset_server.load(CATALOG_PATH)));
 
+    let earch_x = AU as f32 * SPACE_SCALE;
     commands.spawn((
         MyCamera,
         Camera3dBundle {
-            // projection: Projection::Perspective(PerspectiveProjection {
-            //     far: 10000.0, // change the maximum render distance
-            //     ..default()
-            // }),
+            // meshes beyond `far` are culled, keep the whole sky in view
+            projection: Projection::Perspective(PerspectiveProjection {
+                far: SKY_RADIUS * 2.0,
+                ..default()
+            }),
             transform: Transform::from_xyz(earch_x + 100.0, earch_x + 100.0, 0.0)
                 .looking_at(Vec3::new(earch_x, 0.0, 0.0), Vec3::Z),
             ..default()
@@ -369,99 +252,15 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
 #[derive(Component)]
 struct MyCamera;
 
-fn axis(mut gizmos: Gizmos) {
-    gizmos
-        .circle(
-            Vec3::ZERO,
-            Vec3::Y,
-            constant::mercury::DISTANCE_TO_SUN * SPACE_SCALE,
-            Color::SILVER,
-        )
-        .segments(256);
-
-    gizmos
-        .circle(
-            Vec3::ZERO,
-            Vec3::Y,
-            constant::venus::DISTANCE_TO_SUN * SPACE_SCALE,
-            Color::OLIVE,
-        )
-        .segments(256);
-
-    gizmos
-        .circle(
-            Vec3::ZERO,
-            Vec3::Y,
-            constant::earth::DISTANCE_TO_SUN * SPACE_SCALE,
-            Color::rgb_u8(70, 130, 180),
-        )
-        .segments(1024);
-
-    gizmos
-        .circle(
-            Vec3::ZERO,
-            Vec3::Y,
-            constant::mars::DISTANCE_TO_SUN * SPACE_SCALE,
-            Color::rgb_u8(232, 155, 0), // Yellow Ochre
-        )
-        .segments(256);
-
-    gizmos
-        .circle(
-            Vec3::ZERO,
-            Vec3::Y,
-            constant::jupiter::DISTANCE_TO_SUN * SPACE_SCALE,
-            Color::SILVER,
-        )
-        .segments(256);
-
-    gizmos
-        .circle(
-            Vec3::ZERO,
-            Vec3::Y,
-            constant::saturn::DISTANCE_TO_SUN * SPACE_SCALE,
-            Color::SILVER,
-        )
-        .segments(256);
-
-    gizmos
-        .circle(
-            Vec3::ZERO,
-            Vec3::Y,
-            constant::uranus::DISTANCE_TO_SUN * SPACE_SCALE,
-            Color::rgb_u8(32, 178, 170),
-        )
-        .segments(256);
-
-    gizmos
-        .circle(
-            Vec3::ZERO,
-            Vec3::Y,
-            constant::neptune::DISTANCE_TO_SUN * SPACE_SCALE,
-            // Color::rgb_u8(0, 191, 255),
-            Color::BLUE,
-        )
-        .segments(256);
-
+fn axis(mut gizmos: Gizmos, origin: Res<FloatingOrigin>) {
+    let sun = origin.to_render(DVec3::ZERO);
+    gizmos.ray(sun, Vec3::new(-100., 0., 0.), Color::GREEN);
     gizmos.ray(
-        Vec3::new(0., 0., 0.),
-        Vec3::new(-100., 0., 0.),
-        Color::GREEN,
-    );
-    gizmos.ray(
-        Vec3::new(0., 0., 0.),
-        Vec3::new(
-            constant::earth::DISTANCE_TO_SUN * SPACE_SCALE + 100.,
-            0.,
-            0.,
-        ),
+        sun,
+        Vec3::new(AU as f32 * SPACE_SCALE + 100., 0., 0.),
         Color::GREEN,
     );
 
-    gizmos.ray(
-        Vec3::new(0., 0., 0.),
-        Vec3::new(0., -100., 0.),
-        Color::GREEN,
-    );
-    gizmos.ray(Vec3::new(0., 0., 0.), Vec3::new(0., 100., 0.), Color::GREEN);
+    gizmos.ray(sun, Vec3::new(0., -100., 0.), Color::GREEN);
+    gizmos.ray(sun, Vec3::new(0., 100., 0.), Color::GREEN);
 }
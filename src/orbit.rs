@@ -0,0 +1,213 @@
+use std::f64::consts::PI;
+
+use bevy::math::DVec3;
+
+use crate::constant::ONE_DAY_SECONDS;
+
+/// angle between the J2000 equator and the ecliptic, degree
+const OBLIQUITY_J2000: f64 = 23.4392911;
+
+/// Keplerian orbital elements, referenced to the J2000 ecliptic.
+#[derive(Clone, Copy, Debug)]
+pub struct OrbitalElements {
+    pub semi_major_axis: f64, // km
+    pub eccentricity: f64,
+    pub inclination: f64,                 // rad
+    pub longitude_of_ascending_node: f64, // rad
+    pub argument_of_periapsis: f64,       // rad
+    pub mean_anomaly_at_epoch: f64,       // rad
+    pub mean_motion: f64,                 // rad/s
+}
+
+impl OrbitalElements {
+    /// angles are given in degrees, orbital period in days
+    pub const fn new(
+        semi_major_axis: f64,
+        eccentricity: f64,
+        inclination: f64,
+        longitude_of_ascending_node: f64,
+        argument_of_periapsis: f64,
+        mean_anomaly_at_epoch: f64,
+        orbital_period: f64,
+    ) -> Self {
+        Self {
+            semi_major_axis,
+            eccentricity,
+            inclination: inclination.to_radians(),
+            longitude_of_ascending_node: longitude_of_ascending_node.to_radians(),
+            argument_of_periapsis: argument_of_periapsis.to_radians(),
+            mean_anomaly_at_epoch: mean_anomaly_at_epoch.to_radians(),
+            mean_motion: 2.0 * PI / (orbital_period * ONE_DAY_SECONDS as f64),
+        }
+    }
+
+    pub fn mean_anomaly(&self, seconds_since_epoch: f64) -> f64 {
+        (self.mean_anomaly_at_epoch + self.mean_motion * seconds_since_epoch).rem_euclid(2.0 * PI)
+    }
+
+    /// position relative to the focus, in world coordinates
+    pub fn position_at(&self, seconds_since_epoch: f64) -> DVec3 {
+        self.position_at_eccentric_anomaly(solve_kepler(
+            self.mean_anomaly(seconds_since_epoch),
+            self.eccentricity,
+        ))
+    }
+
+    /// point of the orbit, 0 at periapsis and PI at apoapsis
+    pub fn position_at_eccentric_anomaly(&self, eccentric_anomaly: f64) -> DVec3 {
+        let a = self.semi_major_axis;
+        let e = self.eccentricity;
+        let x = a * (eccentric_anomaly.cos() - e);
+        let y = a * (1.0 - e * e).sqrt() * eccentric_anomaly.sin();
+        self.perifocal_to_world(x, y)
+    }
+
+    /// point of the orbit at an angle from periapsis seen from the focus
+    pub fn position_at_true_anomaly(&self, true_anomaly: f64) -> DVec3 {
+        let e = self.eccentricity;
+        let r = self.semi_major_axis * (1.0 - e * e) / (1.0 + e * true_anomaly.cos());
+        self.perifocal_to_world(r * true_anomaly.cos(), r * true_anomaly.sin())
+    }
+
+    /// where the orbit crosses the ecliptic northwards and southwards
+    pub fn nodes(&self) -> (DVec3, DVec3) {
+        let w = self.argument_of_periapsis;
+        (
+            self.position_at_true_anomaly(-w),
+            self.position_at_true_anomaly(PI - w),
+        )
+    }
+
+    /// closest and farthest points from the focus
+    pub fn apsides(&self) -> (DVec3, DVec3) {
+        (
+            self.position_at_eccentric_anomaly(0.0),
+            self.position_at_eccentric_anomaly(PI),
+        )
+    }
+
+    /// velocity relative to the focus in km/s, in world coordinates
+    pub fn velocity_at(&self, seconds_since_epoch: f64) -> DVec3 {
+        let eccentric_anomaly =
+            solve_kepler(self.mean_anomaly(seconds_since_epoch), self.eccentricity);
+        let e = self.eccentricity;
+        let speed = self.mean_motion * self.semi_major_axis / (1.0 - e * eccentric_anomaly.cos());
+        let vx = -speed * eccentric_anomaly.sin();
+        let vy = speed * (1.0 - e * e).sqrt() * eccentric_anomaly.cos();
+        self.perifocal_to_world(vx, vy)
+    }
+
+    /// unit vector perpendicular to the orbital plane, the body orbits counterclockwise around it
+    pub fn normal(&self) -> DVec3 {
+        let (sin_o, cos_o) = self.longitude_of_ascending_node.sin_cos();
+        let (sin_i, cos_i) = self.inclination.sin_cos();
+        ecliptic_to_world(DVec3::new(sin_o * sin_i, -cos_o * sin_i, cos_i))
+    }
+
+    /// rotate a point in the orbital plane (x towards periapsis) into world coordinates
+    fn perifocal_to_world(&self, x: f64, y: f64) -> DVec3 {
+        let (sin_o, cos_o) = self.longitude_of_ascending_node.sin_cos();
+        let (sin_w, cos_w) = self.argument_of_periapsis.sin_cos();
+        let (sin_i, cos_i) = self.inclination.sin_cos();
+
+        let ecliptic = DVec3::new(
+            (cos_o * cos_w - sin_o * sin_w * cos_i) * x
+                + (-cos_o * sin_w - sin_o * cos_w * cos_i) * y,
+            (sin_o * cos_w + cos_o * sin_w * cos_i) * x
+                + (-sin_o * sin_w + cos_o * cos_w * cos_i) * y,
+            (sin_w * sin_i) * x + (cos_w * sin_i) * y,
+        );
+        ecliptic_to_world(ecliptic)
+    }
+}
+
+/// ecliptic frame is z-up, the world is y-up with the ecliptic in the XZ plane
+pub fn ecliptic_to_world(v: DVec3) -> DVec3 {
+    DVec3::new(v.x, v.z, -v.y)
+}
+
+pub fn world_to_ecliptic(v: DVec3) -> DVec3 {
+    DVec3::new(v.x, -v.z, v.y)
+}
+
+/// unit vector towards a right ascension and declination on the J2000 equator, in degrees
+pub fn equatorial_to_world(right_ascension: f64, declination: f64) -> DVec3 {
+    let (sin_a, cos_a) = right_ascension.to_radians().sin_cos();
+    let (sin_d, cos_d) = declination.to_radians().sin_cos();
+    let (sin_e, cos_e) = OBLIQUITY_J2000.to_radians().sin_cos();
+    let (x, y, z) = (cos_d * cos_a, cos_d * sin_a, sin_d);
+    ecliptic_to_world(DVec3::new(x, cos_e * y + sin_e * z, -sin_e * y + cos_e * z))
+}
+
+/// solve Kepler's equation `M = E - e * sin(E)` for the eccentric anomaly `E`
+pub fn solve_kepler(mean_anomaly: f64, eccentricity: f64) -> f64 {
+    let mut e_anomaly = if eccentricity < 0.8 { mean_anomaly } else { PI };
+    for _ in 0..32 {
+        let delta = (e_anomaly - eccentricity * e_anomaly.sin() - mean_anomaly)
+            / (1.0 - eccentricity * e_anomaly.cos());
+        e_anomaly -= delta;
+        if delta.abs() < 1e-12 {
+            break;
+        }
+    }
+    e_anomaly
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{julian_date, J2000};
+
+    fn earth() -> OrbitalElements {
+        OrbitalElements::new(
+            149598261.2,
+            0.01671123,
+            0.0,
+            0.0,
+            102.937682,
+            -2.47311,
+            365.26,
+        )
+    }
+
+    fn seconds_since_j2000(year: i32, month: u32, day: f64) -> f64 {
+        (julian_date(year, month, day) - J2000) * ONE_DAY_SECONDS as f64
+    }
+
+    #[test]
+    fn solve_kepler_converges() {
+        for eccentricity in [0.0, 0.2056, 0.97] {
+            for step in 0..64 {
+                let mean_anomaly = step as f64 / 64.0 * 2.0 * PI;
+                let e_anomaly = solve_kepler(mean_anomaly, eccentricity);
+                let residual = mean_anomaly - (e_anomaly - eccentricity * e_anomaly.sin());
+                assert!(
+                    residual.abs() < 1e-9,
+                    "e = {eccentricity}, M = {mean_anomaly}: off by {residual}"
+                );
+            }
+        }
+        assert_eq!(solve_kepler(1.0, 0.0), 1.0);
+    }
+
+    #[test]
+    fn earth_near_perihelion_in_january() {
+        let orbit = earth();
+        let position = orbit.position_at(seconds_since_j2000(2000, 1, 3.2));
+        let perihelion = orbit.semi_major_axis * (1.0 - orbit.eccentricity);
+        assert!((position.length() - perihelion).abs() < 1e3);
+        // heliocentric ecliptic longitude, the Sun is seen from Earth at about 283°
+        let ecliptic = world_to_ecliptic(position);
+        let longitude = ecliptic.y.atan2(ecliptic.x).to_degrees();
+        assert!((longitude - 102.9).abs() < 1.0, "longitude {longitude}");
+        assert!(ecliptic.z.abs() < 1.0);
+    }
+
+    #[test]
+    fn earth_near_aphelion_in_july() {
+        let orbit = earth();
+        let position = orbit.position_at(seconds_since_j2000(2024, 7, 5.0));
+        let aphelion = orbit.semi_major_axis * (1.0 + orbit.eccentricity);
+        assert!((position.length() - aphelion).abs() < 1e4);
+    }
+}
@@ -0,0 +1,169 @@
+use std::f64::consts::TAU;
+
+use bevy::{math::DVec3, prelude::*};
+
+use crate::{
+    bindings::Action,
+    catalog::{BodyCatalog, BodyCatalogHandle},
+    constant::{AU, SPACE_SCALE},
+    controls::Actions,
+    floating_origin::FloatingOrigin,
+    picking::Pickable,
+    planets::{CelestialBody, Planets},
+    scale::DisplayScale,
+};
+
+const ORBIT_SEGMENTS: usize = 512;
+/// markers are sized relative to their distance to the orbit's focus
+const MARKER_SIZE: f32 = 0.02;
+/// circles of the ecliptic grid, AU
+const GRID_CIRCLES: [f64; 8] = [0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 30.0, 40.0];
+const GRID_SPOKES: usize = 12;
+
+/// optional gizmos drawn over the scene
+#[derive(Resource)]
+pub struct Overlays {
+    pub labels: bool,
+    pub trails: bool,
+    pub rotation_axes: bool,
+    pub ecliptic_grid: bool,
+    pub nodes: bool,
+    pub apsides: bool,
+    pub constellations: bool,
+}
+
+impl Default for Overlays {
+    fn default() -> Self {
+        Self {
+            labels: true,
+            trails: true,
+            rotation_axes: false,
+            ecliptic_grid: false,
+            nodes: false,
+            apsides: false,
+            constellations: false,
+        }
+    }
+}
+
+pub fn toggle_overlays(actions: Res<Actions>, mut overlays: ResMut<Overlays>) {
+    let overlays = overlays.as_mut();
+    for (action, shown) in [
+        (Action::ToggleLabels, &mut overlays.labels),
+        (Action::ToggleTrails, &mut overlays.trails),
+        (Action::ToggleRotationAxes, &mut overlays.rotation_axes),
+        (Action::ToggleEclipticGrid, &mut overlays.ecliptic_grid),
+        (Action::ToggleNodes, &mut overlays.nodes),
+        (Action::ToggleApsides, &mut overlays.apsides),
+        (Action::ToggleConstellations, &mut overlays.constellations),
+    ] {
+        if actions.just_pressed(action) {
+            *shown = !*shown;
+        }
+    }
+}
+
+/// every orbit as the ellipse given by its elements, placed like the bodies by the scale mode;
+/// satellites are drawn around their moving parent
+pub fn draw_orbits(
+    mut gizmos: Gizmos,
+    catalog_handle: Res<BodyCatalogHandle>,
+    catalogs: Res<Assets<BodyCatalog>>,
+    origin: Res<FloatingOrigin>,
+    scale: Res<DisplayScale>,
+    overlays: Res<Overlays>,
+    bodies: Query<(&Name, &Transform, &CelestialBody)>,
+) {
+    let Some(catalog) = catalogs.get(&catalog_handle.0) else {
+        return;
+    };
+    for body in &catalog.bodies {
+        let (Some(orbit), Some(color)) = (body.orbit, body.orbit_color()) else {
+            continue;
+        };
+        let (center, parent_radius) = match &body.parent {
+            Some(parent) => match bodies.iter().find(|(name, ..)| name.as_str() == parent) {
+                Some((_, transform, parent)) => (transform.translation, Some(parent.radius())),
+                None => continue,
+            },
+            None => (origin.to_render(DVec3::ZERO), None),
+        };
+        let to_render = |offset: DVec3| {
+            center
+                + scale
+                    .offset(offset * SPACE_SCALE as f64, parent_radius)
+                    .as_vec3()
+        };
+        let elements = orbit.elements();
+
+        gizmos.linestrip(
+            (0..=ORBIT_SEGMENTS).map(|i| {
+                to_render(
+                    elements.position_at_eccentric_anomaly(TAU * i as f64 / ORBIT_SEGMENTS as f64),
+                )
+            }),
+            color,
+        );
+        if overlays.nodes {
+            let (ascending, descending) = elements.nodes();
+            let (ascending, descending) = (to_render(ascending), to_render(descending));
+            gizmos.line(ascending, descending, color.with_a(0.3));
+            draw_marker(&mut gizmos, center, ascending, Color::GREEN);
+            draw_marker(&mut gizmos, center, descending, Color::RED);
+        }
+        if overlays.apsides {
+            let (periapsis, apoapsis) = elements.apsides();
+            let (periapsis, apoapsis) = (to_render(periapsis), to_render(apoapsis));
+            gizmos.line(periapsis, apoapsis, color.with_a(0.3));
+            draw_marker(&mut gizmos, center, periapsis, Color::YELLOW);
+            draw_marker(&mut gizmos, center, apoapsis, Color::CYAN);
+        }
+    }
+}
+
+fn draw_marker(gizmos: &mut Gizmos, center: Vec3, position: Vec3, color: Color) {
+    let size = (position - center).length() * MARKER_SIZE;
+    gizmos.sphere(position, Quat::IDENTITY, size, color);
+}
+
+/// polar grid in the ecliptic plane around the Sun, circles every few AU
+pub fn draw_ecliptic_grid(
+    mut gizmos: Gizmos,
+    overlays: Res<Overlays>,
+    origin: Res<FloatingOrigin>,
+    scale: Res<DisplayScale>,
+) {
+    if !overlays.ecliptic_grid {
+        return;
+    }
+    let sun = origin.to_render(DVec3::ZERO);
+    let color = Color::rgba(0.5, 0.5, 0.5, 0.4);
+    let au = AU * SPACE_SCALE as f64;
+    for radius in GRID_CIRCLES {
+        let radius = scale.distance(radius * au) as f32;
+        gizmos.circle(sun, Vec3::Y, radius, color).segments(256);
+    }
+    let outer = scale.distance(GRID_CIRCLES[GRID_CIRCLES.len() - 1] * au) as f32;
+    for i in 0..GRID_SPOKES {
+        let angle = std::f32::consts::TAU * i as f32 / GRID_SPOKES as f32;
+        let direction = Vec3::new(angle.cos(), 0.0, angle.sin());
+        gizmos.line(sun, sun + direction * outer, color);
+    }
+}
+
+/// each body's spin axis through its poles, red on the side it turns counterclockwise around
+pub fn draw_rotation_axes(
+    overlays: Res<Overlays>,
+    bodies: Query<(&Transform, &CelestialBody, &Pickable)>,
+    mut gizmos: Gizmos,
+) {
+    if !overlays.rotation_axes {
+        return;
+    }
+    for (transform, body, pickable) in &bodies {
+        let axis = body.spin_axis() * pickable.radius * 1.5;
+        let center = transform.translation;
+        gizmos.line(center, center + axis, Color::RED);
+        gizmos.line(center - axis, center, Color::BLUE);
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::camera::CameraFocus;
+
+/// smallest on-screen radius a pickable keeps, so far away bodies can still be clicked
+const MIN_PICK_PIXELS: f32 = 8.0;
+/// a left press and release further apart than this is a camera drag, not a click
+const CLICK_TOLERANCE_PIXELS: f32 = 4.0;
+
+/// bounding sphere used for mouse picking; the GLB meshes are far too small at real scale
+#[derive(Component)]
+pub struct Pickable {
+    pub radius: f32,
+}
+
+/// the entity under the mouse cursor, with the bounding sphere it was picked with
+#[derive(Resource, Default)]
+pub struct Hovered {
+    pub entity: Option<Entity>,
+    center: Vec3,
+    radius: f32,
+}
+
+pub fn pick_bodies(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform, &Projection)>,
+    pickables: Query<(Entity, &GlobalTransform, &Pickable)>,
+    interactions: Query<&Interaction>,
+    mut hovered: ResMut<Hovered>,
+) {
+    hovered.entity = None;
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Ok((camera, camera_transform, projection)) = cameras.get_single() else {
+        return;
+    };
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    if interactions.iter().any(|i| *i != Interaction::None) {
+        return;
+    }
+    let Some(ray) = camera.viewport_to_world(camera_transform, cursor) else {
+        return;
+    };
+    let world_per_pixel = match projection {
+        Projection::Perspective(p) => 2.0 * (p.fov / 2.0).tan() / window.height(),
+        Projection::Orthographic(_) => 0.0,
+    };
+
+    let mut nearest = None;
+    for (entity, transform, pickable) in &pickables {
+        let center = transform.translation();
+        let to_center = center - ray.origin;
+        let t = to_center.dot(ray.direction);
+        if t <= 0.0 {
+            continue;
+        }
+        let radius = pickable.radius.max(t * world_per_pixel * MIN_PICK_PIXELS);
+        let miss = (to_center - ray.direction * t).length();
+        if miss <= radius && nearest.is_none_or(|(_, nearest_t)| t < nearest_t) {
+            nearest = Some((entity, t));
+            hovered.center = center;
+            hovered.radius = radius;
+        }
+    }
+    hovered.entity = nearest.map(|(entity, _)| entity);
+}
+
+pub fn highlight_hovered(
+    hovered: Res<Hovered>,
+    cameras: Query<&GlobalTransform, With<Camera>>,
+    mut gizmos: Gizmos,
+) {
+    let (Some(_), Ok(camera_transform)) = (hovered.entity, cameras.get_single()) else {
+        return;
+    };
+    gizmos
+        .circle(
+            hovered.center,
+            camera_transform.back(),
+            hovered.radius * 1.2,
+            Color::YELLOW,
+        )
+        .segments(64);
+}
+
+pub fn click_to_focus(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    buttons: Res<Input<MouseButton>>,
+    hovered: Res<Hovered>,
+    mut camera_focus: ResMut<CameraFocus>,
+    mut press_position: Local<Option<Vec2>>,
+) {
+    let Some(cursor) = windows.get_single().ok().and_then(|w| w.cursor_position()) else {
+        return;
+    };
+    if buttons.just_pressed(MouseButton::Left) {
+        *press_position = hovered.entity.map(|_| cursor);
+    }
+    if buttons.just_released(MouseButton::Left) {
+        let pressed = press_position.take();
+        if let (Some(pressed), Some(entity)) = (pressed, hovered.entity) {
+            if pressed.distance(cursor) <= CLICK_TOLERANCE_PIXELS {
+                camera_focus.focus = Some(entity);
+            }
+        }
+    }
+}
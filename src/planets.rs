@@ -1,94 +1,103 @@
-use crate::constant::TIME_SCALE;
-use bevy::prelude::*;
+use std::f64::consts::TAU;
+
+use crate::{
+    clock::SimulationClock,
+    constant::SPACE_SCALE,
+    floating_origin::{PreviousPosition, WorldPosition},
+    orbit::OrbitalElements,
+};
+use bevy::{math::DVec3, prelude::*};
 
 pub trait Planets {
     fn radius(&self) -> f32;
-    fn distance_to_sun(&self) -> f32;
+    /// rad/s, negative for retrograde rotation
     fn rotation_velocity(&self) -> f32;
-    fn orbital_velocity(&self) -> f32;
+    /// north pole in world coordinates, the body spins counterclockwise around it
+    /// when `rotation_velocity` is positive
+    fn pole(&self) -> Vec3;
+    /// `None` for bodies resting at the origin, like the Sun
+    fn orbit(&self) -> Option<&OrbitalElements>;
     fn name(&self) -> &str;
-}
-
-#[derive(Component)]
-pub struct CommonPlanets {
-    distance_to_sun: f32,
-    radius: f32,
-    rotation_velocity: f32,
-    orbital_velocity: f32,
-    name: String,
-}
 
-impl CommonPlanets {
-    pub fn new(
-        distance_to_sun: f32,
-        radius: f32,
-        rotation_velocity: f32,
-        orbital_velocity: f32,
-        name: String,
-    ) -> Self {
-        Self {
-            distance_to_sun,
-            radius,
-            rotation_velocity,
-            orbital_velocity,
-            name,
-        }
-    }
-}
-
-impl Planets for CommonPlanets {
     fn distance_to_sun(&self) -> f32 {
-        self.distance_to_sun
+        self.orbit()
+            .map_or(0.0, |orbit| orbit.semi_major_axis as f32)
     }
-
-    fn radius(&self) -> f32 {
-        self.radius
+    fn orbital_velocity(&self) -> f32 {
+        self.orbit().map_or(0.0, |orbit| orbit.mean_motion as f32)
     }
-
-    fn rotation_velocity(&self) -> f32 {
-        self.rotation_velocity
+    /// seconds, `None` without an orbit
+    fn orbital_period(&self) -> Option<f32> {
+        self.orbit().map(|orbit| (TAU / orbit.mean_motion) as f32)
     }
-    fn orbital_velocity(&self) -> f32 {
-        self.orbital_velocity
+    /// seconds
+    fn rotation_period(&self) -> f32 {
+        TAU as f32 / self.rotation_velocity()
     }
-
-    fn name(&self) -> &str {
-        &self.name
+    /// rotates the y axis onto the pole
+    fn orientation(&self) -> Quat {
+        Quat::from_rotation_arc(Vec3::Y, self.pole())
+    }
+    /// the pole the body turns counterclockwise around
+    fn spin_axis(&self) -> Vec3 {
+        self.pole() * self.rotation_velocity().signum()
+    }
+    /// radian, angle between the spin axis and the orbit normal; over 90° spins retrograde
+    fn axial_tilt(&self) -> f32 {
+        let normal = self
+            .orbit()
+            .map_or(Vec3::Y, |orbit| orbit.normal().as_vec3());
+        self.spin_axis().angle_between(normal)
+    }
+    fn semi_major_axis(&self) -> Option<f64> {
+        self.orbit().map(|orbit| orbit.semi_major_axis)
+    }
+    fn eccentricity(&self) -> Option<f64> {
+        self.orbit().map(|orbit| orbit.eccentricity)
+    }
+    fn inclination(&self) -> Option<f64> {
+        self.orbit().map(|orbit| orbit.inclination)
+    }
+    fn longitude_of_ascending_node(&self) -> Option<f64> {
+        self.orbit().map(|orbit| orbit.longitude_of_ascending_node)
+    }
+    fn argument_of_periapsis(&self) -> Option<f64> {
+        self.orbit().map(|orbit| orbit.argument_of_periapsis)
+    }
+    fn mean_anomaly_at_epoch(&self) -> Option<f64> {
+        self.orbit().map(|orbit| orbit.mean_anomaly_at_epoch)
     }
 }
 
+/// every star, planet and moon of the simulation
 #[derive(Component)]
-pub struct Earth {
-    distance_to_sun: f32,
+pub struct CelestialBody {
     radius: f32,
     rotation_velocity: f32,
-    orbital_velocity: f32,
+    pole: Vec3,
+    orbit: Option<OrbitalElements>,
     name: String,
 }
 
-impl Earth {
+impl CelestialBody {
     pub fn new(
-        distance_to_sun: f32,
         radius: f32,
         rotation_velocity: f32,
-        orbital_velocity: f32,
+        pole: Vec3,
+        orbit: Option<OrbitalElements>,
         name: String,
     ) -> Self {
         Self {
-            distance_to_sun,
             radius,
             rotation_velocity,
-            orbital_velocity,
+            pole,
+            orbit,
             name,
         }
     }
 }
 
-impl Planets for Earth {
-    fn distance_to_sun(&self) -> f32 {
-        self.distance_to_sun
-    }
-
+impl Planets for CelestialBody {
     fn radius(&self) -> f32 {
         self.radius
     }
@@ -96,8 +105,11 @@ impl Planets for Earth {
     fn rotation_velocity(&self) -> f32 {
         self.rotation_velocity
     }
-    fn orbital_velocity(&self) -> f32 {
-        self.orbital_velocity
+    fn pole(&self) -> Vec3 {
+        self.pole
+    }
+    fn orbit(&self) -> Option<&OrbitalElements> {
+        self.orbit.as_ref()
     }
 
     fn name(&self) -> &str {
@@ -105,35 +117,80 @@ impl Planets for Earth {
     }
 }
 
-pub fn move_planets<T>(time: Res<Time>, mut query: Query<(&mut Transform, &T), Without<Earth>>)
-where
-    T: Planets + bevy::prelude::Component,
-{
-    for (mut transform, planet) in &mut query {
-        // let movement = transf.forward() + transf.left();
-        transform.rotate_y(TIME_SCALE * planet.rotation_velocity() * time.delta_seconds());
+/// the body at the center of the system
+#[derive(Component)]
+pub struct Star;
+
+/// the body we live on
+#[derive(Component)]
+pub struct Earth;
 
-        let elapsed_seconds = time.elapsed_seconds();
-        let angle = elapsed_seconds * TIME_SCALE * planet.orbital_velocity();
-        transform.translation = Vec3::new(
-            planet.distance_to_sun() * angle.cos(),
-            0.0,
-            planet.distance_to_sun() * angle.sin(),
-        );
+/// the body orbits another entity instead of the Sun
+#[derive(Component)]
+pub struct OrbitsParent(pub Entity);
+
+/// places each body relative to the body it orbits, see `follow_orbit_parents`
+/// also where each body was before the clock advanced this frame
+pub fn move_planets(
+    clock: Res<SimulationClock>,
+    mut query: Query<(
+        &mut Transform,
+        &mut WorldPosition,
+        &mut PreviousPosition,
+        &CelestialBody,
+    )>,
+) {
+    let seconds_since_epoch = clock.seconds_since_j2000();
+    for (mut transform, mut position, mut previous, body) in &mut query {
+        transform.rotation =
+            body.orientation() * rotation_at(body.rotation_velocity(), seconds_since_epoch);
+        if let Some(orbit) = body.orbit() {
+            position.0 = orbit.position_at(seconds_since_epoch) * SPACE_SCALE as f64;
+            previous.0 =
+                orbit.position_at(seconds_since_epoch - clock.delta_seconds) * SPACE_SCALE as f64;
+        } else {
+            previous.0 = position.0;
+        }
     }
 }
 
-pub fn move_earth(time: Res<Time>, mut query: Query<(&mut Transform, &Earth)>) {
-    for (mut transform, planet) in &mut query {
-        // let movement = transf.forward() + transf.left();
-        transform.rotate_y(TIME_SCALE * planet.rotation_velocity() * time.delta_seconds());
-
-        let elapsed_seconds = time.elapsed_seconds();
-        let angle = elapsed_seconds * TIME_SCALE * planet.orbital_velocity();
-        transform.translation = Vec3::new(
-            planet.distance_to_sun() * angle.cos(),
-            0.0,
-            planet.distance_to_sun() * angle.sin(),
-        );
+/// turn the parent-relative positions written by `move_planets` into absolute ones,
+/// walking up the whole chain so satellites of satellites work too
+pub fn follow_orbit_parents(
+    satellites: Query<(Entity, &OrbitsParent)>,
+    mut positions: Query<(&mut WorldPosition, &mut PreviousPosition)>,
+) {
+    let count = satellites.iter().len();
+    let offsets: Vec<(Entity, DVec3, DVec3)> = satellites
+        .iter()
+        .map(|(entity, parent)| {
+            let mut offset = DVec3::ZERO;
+            let mut previous_offset = DVec3::ZERO;
+            let mut next = Some(parent.0);
+            // `spawn_bodies` rejects cycles, the bound only keeps a bad link from hanging
+            for _ in 0..count {
+                let Some(parent) = next else {
+                    break;
+                };
+                if let Ok((position, previous)) = positions.get(parent) {
+                    offset += position.0;
+                    previous_offset += previous.0;
+                }
+                next = satellites.get(parent).ok().map(|(_, parent)| parent.0);
+            }
+            (entity, offset, previous_offset)
+        })
+        .collect();
+
+    for (entity, offset, previous_offset) in offsets {
+        if let Ok((mut position, mut previous)) = positions.get_mut(entity) {
+            position.0 += offset;
+            previous.0 += previous_offset;
+        }
     }
 }
+
+/// spin about the local y axis, taken from the absolute simulation time so that jumping in time keeps it consistent
+pub fn rotation_at(rotation_velocity: f32, seconds_since_epoch: f64) -> Quat {
+    Quat::from_rotation_y((rotation_velocity as f64 * seconds_since_epoch).rem_euclid(TAU) as f32)
+}
@@ -0,0 +1,127 @@
+use std::f32::consts::TAU;
+
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::Indices,
+        render_resource::{Extent3d, PrimitiveTopology, TextureDimension, TextureFormat},
+    },
+};
+
+use crate::{
+    catalog::{BodyCatalog, BodyCatalogHandle, RingDescriptor},
+    constant::PLANET_GLTF_SCALE,
+    planets::{CelestialBody, Planets},
+};
+
+/// texels of the radial opacity profile, enough to show the Encke Gap
+const RING_TEXTURE_WIDTH: u32 = 1024;
+const RING_SEGMENTS: u32 = 256;
+
+#[derive(Component)]
+pub struct Rings;
+
+/// rings are children of their planet, in its equatorial plane, so they follow its axial tilt
+pub fn attach_rings(
+    mut commands: Commands,
+    catalog_handle: Res<BodyCatalogHandle>,
+    catalogs: Res<Assets<BodyCatalog>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    bodies: Query<(Entity, &Name, &CelestialBody), Added<CelestialBody>>,
+) {
+    let Some(catalog) = catalogs.get(&catalog_handle.0) else {
+        return;
+    };
+    for (entity, name, body) in &bodies {
+        let Some(rings) = catalog
+            .bodies
+            .iter()
+            .find(|descriptor| descriptor.name == name.as_str())
+            .and_then(|descriptor| descriptor.rings.as_ref())
+        else {
+            continue;
+        };
+        // radii relative to the model, which is scaled with the body
+        let to_model = 1.0 / (body.radius() * PLANET_GLTF_SCALE);
+        let (r, g, b) = rings.color;
+        let material = StandardMaterial {
+            base_color: Color::rgb_u8(r, g, b),
+            base_color_texture: Some(images.add(ring_texture(rings))),
+            alpha_mode: AlphaMode::Blend,
+            double_sided: true,
+            cull_mode: None,
+            ..default()
+        };
+        commands.entity(entity).with_children(|parent| {
+            parent.spawn((
+                PbrBundle {
+                    mesh: meshes.add(annulus(
+                        rings.inner_radius() * to_model,
+                        rings.outer_radius() * to_model,
+                        RING_SEGMENTS,
+                    )),
+                    material: materials.add(material),
+                    ..default()
+                },
+                Rings,
+            ));
+        });
+    }
+}
+
+/// flat ring in the xz plane, u running from the inner to the outer edge
+fn annulus(inner_radius: f32, outer_radius: f32, segments: u32) -> Mesh {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut indices = Vec::new();
+    for i in 0..=segments {
+        let (sin, cos) = (TAU * i as f32 / segments as f32).sin_cos();
+        for (radius, u) in [(inner_radius, 0.0), (outer_radius, 1.0)] {
+            positions.push([cos * radius, 0.0, sin * radius]);
+            normals.push([0.0, 1.0, 0.0]);
+            uvs.push([u, i as f32 / segments as f32]);
+        }
+    }
+    for i in 0..segments {
+        let a = 2 * i;
+        indices.extend([a, a + 1, a + 2, a + 1, a + 3, a + 2]);
+    }
+    Mesh::new(PrimitiveTopology::TriangleList)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_indices(Some(Indices::U32(indices)))
+}
+
+/// opacity from the inner to the outer edge; a texel only partly covered by a narrow ring
+/// gets the covered fraction, so rings a few km wide stay faint instead of vanishing
+fn ring_texture(rings: &RingDescriptor) -> Image {
+    let inner = rings.inner_radius();
+    let texel = (rings.outer_radius() - inner) / RING_TEXTURE_WIDTH as f32;
+    let mut data = Vec::with_capacity(RING_TEXTURE_WIDTH as usize * 4);
+    for i in 0..RING_TEXTURE_WIDTH {
+        let start = inner + i as f32 * texel;
+        let end = start + texel;
+        let opacity: f32 = rings
+            .bands
+            .iter()
+            .map(|band| {
+                (band.outer.min(end) - band.inner.max(start)).max(0.0) / texel * band.opacity
+            })
+            .sum();
+        data.extend([255, 255, 255, (opacity.min(1.0) * 255.0) as u8]);
+    }
+    Image::new(
+        Extent3d {
+            width: RING_TEXTURE_WIDTH,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
@@ -0,0 +1,236 @@
+use bevy::{math::DVec3, prelude::*};
+
+use crate::{
+    bindings::{Action, Bindings},
+    camera::ease_in_out_cubic,
+    constant::{AU, PLANET_GLTF_SCALE, SPACE_SCALE},
+    controls::Actions,
+    floating_origin::WorldPosition,
+    picking::Pickable,
+    planets::{CelestialBody, OrbitsParent, Planets, Star},
+};
+
+/// seconds to animate from one scale mode to the next
+const TRANSITION_SECONDS: f32 = 1.5;
+/// distance below which `LogarithmicDistances` stays roughly linear
+const LOG_DISTANCE_UNIT: f64 = 0.1 * AU;
+
+/// how bodies are sized and placed on screen, the simulation itself always runs at true scale
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum ScaleMode {
+    /// real radii and distances
+    #[default]
+    True,
+    /// real distances, enlarged bodies
+    ExaggeratedRadii,
+    /// enlarged bodies, distances to the Sun on a logarithmic scale
+    LogarithmicDistances,
+    /// enlarged bodies, distances to the Sun compressed with a square root
+    CompressedOrbits,
+}
+
+impl ScaleMode {
+    pub fn next(self) -> Self {
+        match self {
+            ScaleMode::True => ScaleMode::ExaggeratedRadii,
+            ScaleMode::ExaggeratedRadii => ScaleMode::LogarithmicDistances,
+            ScaleMode::LogarithmicDistances => ScaleMode::CompressedOrbits,
+            ScaleMode::CompressedOrbits => ScaleMode::True,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ScaleMode::True => "True scale",
+            ScaleMode::ExaggeratedRadii => "Exaggerated radii",
+            ScaleMode::LogarithmicDistances => "Logarithmic distances",
+            ScaleMode::CompressedOrbits => "Compressed orbits",
+        }
+    }
+
+    fn radius_factor(self, star: bool) -> f64 {
+        match (self, star) {
+            (ScaleMode::True, _) => 1.0,
+            // the Sun would swallow Mercury otherwise
+            (_, true) => 20.0,
+            (ScaleMode::ExaggeratedRadii, false) => 500.0,
+            (ScaleMode::LogarithmicDistances, false) => 200.0,
+            (ScaleMode::CompressedOrbits, false) => 100.0,
+        }
+    }
+
+    /// distance to the Sun on screen
+    fn distance(self, distance: f64) -> f64 {
+        let au = AU * SPACE_SCALE as f64;
+        match self {
+            ScaleMode::True | ScaleMode::ExaggeratedRadii => distance,
+            ScaleMode::LogarithmicDistances => {
+                au * (1.0 + distance / (LOG_DISTANCE_UNIT * SPACE_SCALE as f64)).ln()
+            }
+            ScaleMode::CompressedOrbits => au * (distance / au).sqrt(),
+        }
+    }
+
+    /// distance to the parent on screen; counted in parent radii, so moons stay
+    /// outside their enlarged parent, and compressed like the distances to the Sun
+    fn satellite_distance(self, distance: f64, parent_radius: f64) -> f64 {
+        let scaled_radius = parent_radius * self.radius_factor(false);
+        match self {
+            ScaleMode::True => distance,
+            _ => scaled_radius * (distance / parent_radius).sqrt(),
+        }
+    }
+}
+
+/// the current scale mode and the animation from the previous one
+#[derive(Resource, Default)]
+pub struct DisplayScale {
+    pub mode: ScaleMode,
+    previous: ScaleMode,
+    elapsed: f32,
+}
+
+impl DisplayScale {
+    pub fn set(&mut self, mode: ScaleMode) {
+        if mode != self.mode {
+            self.previous = self.mode;
+            self.mode = mode;
+            self.elapsed = 0.0;
+        }
+    }
+
+    /// moving from one mode to the next
+    pub fn is_animating(&self) -> bool {
+        self.previous != self.mode && self.elapsed < TRANSITION_SECONDS
+    }
+
+    fn blend(&self, value: impl Fn(ScaleMode) -> f64) -> f64 {
+        let progress = ease_in_out_cubic((self.elapsed / TRANSITION_SECONDS).min(1.0)) as f64;
+        value(self.previous) + (value(self.mode) - value(self.previous)) * progress
+    }
+
+    /// rendered radius of a body, in world units
+    pub fn radius(&self, radius: f32, star: bool) -> f32 {
+        self.blend(|mode| radius as f64 * SPACE_SCALE as f64 * mode.radius_factor(star)) as f32
+    }
+
+    /// rendered distance to the Sun for a world distance
+    pub fn distance(&self, distance: f64) -> f64 {
+        self.blend(|mode| mode.distance(distance))
+    }
+
+    /// rendered offset from the parent for a world offset, `parent_radius` in km,
+    /// or from the Sun without a parent
+    pub fn offset(&self, offset: DVec3, parent_radius: Option<f32>) -> DVec3 {
+        let distance = offset.length();
+        let scaled = match parent_radius {
+            Some(parent_radius) => {
+                let parent_radius = parent_radius as f64 * SPACE_SCALE as f64;
+                self.blend(|mode| mode.satellite_distance(distance, parent_radius))
+            }
+            None => self.distance(distance),
+        };
+        offset.normalize_or_zero() * scaled
+    }
+}
+
+/// where a body is drawn under the current `ScaleMode`, in world units;
+/// `apply_floating_origin` uses it instead of the `WorldPosition`
+#[derive(Component, Default)]
+pub struct ScaledPosition(pub DVec3);
+
+#[derive(Component)]
+pub struct ScaleModeText;
+
+type Bodies<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static CelestialBody,
+        &'static WorldPosition,
+        Option<&'static OrbitsParent>,
+        Has<Star>,
+    ),
+>;
+
+/// size and place every body according to the scale mode
+pub fn scale_bodies(
+    time: Res<Time>,
+    mut scale: ResMut<DisplayScale>,
+    bodies: Bodies,
+    mut scaled: Query<(&mut ScaledPosition, &mut Transform, &mut Pickable)>,
+) {
+    scale.elapsed = (scale.elapsed + time.delta_seconds()).min(TRANSITION_SECONDS);
+    for (entity, body, _, _, star) in &bodies {
+        let radius = scale.radius(body.radius(), star);
+        if let Ok((mut position, mut transform, mut pickable)) = scaled.get_mut(entity) {
+            position.0 = scaled_position(&scale, entity, &bodies);
+            transform.scale = Vec3::splat(PLANET_GLTF_SCALE * radius);
+            pickable.radius = radius;
+        }
+    }
+}
+
+fn scaled_position(scale: &DisplayScale, entity: Entity, bodies: &Bodies) -> DVec3 {
+    let Ok((_, _, position, parent, _)) = bodies.get(entity) else {
+        return DVec3::ZERO;
+    };
+    match parent.and_then(|parent| bodies.get(parent.0).ok()) {
+        Some((parent, parent_body, parent_position, _, _)) => {
+            scaled_position(scale, parent, bodies)
+                + scale.offset(position.0 - parent_position.0, Some(parent_body.radius()))
+        }
+        None => scale.offset(position.0, None),
+    }
+}
+
+pub fn setup_scale_mode_text(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                        ..default()
+                    },
+                ),
+                ScaleModeText,
+            ));
+        });
+}
+
+pub fn cycle_scale_mode(
+    actions: Res<Actions>,
+    bindings: Res<Bindings>,
+    mut scale: ResMut<DisplayScale>,
+    mut text: Query<&mut Text, With<ScaleModeText>>,
+    mut shown: Local<Option<ScaleMode>>,
+) {
+    if actions.just_pressed(Action::CycleScale) {
+        let next = scale.mode.next();
+        scale.set(next);
+    }
+    if *shown != Some(scale.mode) || bindings.is_changed() {
+        *shown = Some(scale.mode);
+        for mut text in &mut text {
+            text.sections[0].value = format!(
+                "{}  [{}] change scale",
+                scale.mode.label(),
+                bindings.label(Action::CycleScale)
+            );
+        }
+    }
+}
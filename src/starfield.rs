@@ -0,0 +1,434 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    pbr::{NotShadowCaster, NotShadowReceiver},
+    prelude::*,
+    render::{
+        mesh::Indices,
+        render_resource::{Extent3d, PrimitiveTopology, TextureDimension, TextureFormat},
+    },
+    utils::{BoxedFuture, HashMap},
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{orbit::equatorial_to_world, overlays::Overlays};
+
+pub const STARS_PATH: &str = "bright_stars.csv";
+pub const CONSTELLATIONS_PATH: &str = "northern_sky.constellations.ron";
+
+/// km, the stars sit on a sphere around the camera, far outside the planets' orbits
+pub const SKY_RADIUS: f32 = 1.0e11;
+/// faintest magnitude of the bundled catalog
+const FAINTEST_MAGNITUDE: f32 = 6.5;
+/// roughly one pixel of a 1080 pixel high window with the default field of view
+const PIXEL_ANGLE: f32 = 0.0008;
+const STAR_TEXTURE_SIZE: u32 = 32;
+/// constellation lines are drawn just inside the stars
+const CONSTELLATION_RADIUS: f32 = SKY_RADIUS * 0.9;
+
+/// one star of `assets/bright_stars.csv`, angles in degrees on the J2000 equator
+#[derive(Clone, Copy)]
+pub struct BrightStar {
+    pub hip: u32,
+    pub right_ascension: f64,
+    pub declination: f64,
+    pub magnitude: f32,
+    pub color_index: f32, // B-V
+}
+
+impl BrightStar {
+    pub fn direction(&self) -> Vec3 {
+        equatorial_to_world(self.right_ascension, self.declination).as_vec3()
+    }
+
+    /// pixels across, brighter stars are drawn larger
+    fn size(&self) -> f32 {
+        (2.0 + (FAINTEST_MAGNITUDE - self.magnitude) * 0.8).min(9.0)
+    }
+
+    /// 1 for the brightest stars, a quarter of that at the catalog's limit
+    fn brightness(&self) -> f32 {
+        (0.25 + 0.75 * (FAINTEST_MAGNITUDE - self.magnitude) / (FAINTEST_MAGNITUDE + 1.0))
+            .clamp(0.25, 1.0)
+    }
+
+    /// black body color for the temperature given by the B-V index
+    fn color(&self) -> Color {
+        let bv = self.color_index.clamp(-0.4, 2.0);
+        // Ballesteros (2012), then Tanner Helland's fit of black body colors in hundreds of kelvin
+        let t = 46.0 * (1.0 / (0.92 * bv + 1.7) + 1.0 / (0.92 * bv + 0.62));
+        let r = if t <= 66.0 {
+            255.0
+        } else {
+            329.699 * (t - 60.0).powf(-0.133_204_76)
+        };
+        let g = if t <= 66.0 {
+            99.470_8 * t.ln() - 161.119_57
+        } else {
+            288.122_17 * (t - 60.0).powf(-0.075_514_85)
+        };
+        let b = if t >= 66.0 {
+            255.0
+        } else if t <= 19.0 {
+            0.0
+        } else {
+            138.517_73 * (t - 10.0).ln() - 305.044_8
+        };
+        Color::rgb(
+            r.clamp(0.0, 255.0) / 255.0,
+            g.clamp(0.0, 255.0) / 255.0,
+            b.clamp(0.0, 255.0) / 255.0,
+        )
+    }
+}
+
+#[derive(Asset, TypePath)]
+pub struct StarCatalog {
+    pub stars: Vec<BrightStar>,
+}
+
+/// figures of `assets/northern_sky.constellations.ron`
+#[derive(Asset, TypePath, Deserialize)]
+pub struct Constellations {
+    pub constellations: Vec<Constellation>,
+}
+
+#[derive(Deserialize)]
+pub struct Constellation {
+    pub name: String,
+    /// polylines through stars given by Hipparcos number
+    pub lines: Vec<Vec<u32>>,
+}
+
+#[derive(Default)]
+pub struct StarCatalogLoader;
+
+#[derive(Debug, Error)]
+pub enum StarCatalogLoaderError {
+    #[error("could not read star catalog: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse star catalog line {0}")]
+    Parse(usize),
+}
+
+impl AssetLoader for StarCatalogLoader {
+    type Asset = StarCatalog;
+    type Settings = ();
+    type Error = StarCatalogLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<StarCatalog, StarCatalogLoaderError>> {
+        Box::pin(async move {
+            let mut text = String::new();
+            reader.read_to_string(&mut text).await?;
+            let stars = text
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| {
+                    !(line.is_empty() || line.starts_with('#') || line.starts_with("hip"))
+                })
+                .map(|(number, line)| {
+                    parse_star(line).ok_or(StarCatalogLoaderError::Parse(number + 1))
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(StarCatalog { stars })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["csv"]
+    }
+}
+
+/// `hip,ra,dec,vmag,bv`
+fn parse_star(line: &str) -> Option<BrightStar> {
+    let mut fields = line.split(',').map(str::trim);
+    let star = BrightStar {
+        hip: fields.next()?.parse().ok()?,
+        right_ascension: fields.next()?.parse().ok()?,
+        declination: fields.next()?.parse().ok()?,
+        magnitude: fields.next()?.parse().ok()?,
+        color_index: fields.next()?.parse().ok()?,
+    };
+    fields.next().is_none().then_some(star)
+}
+
+#[derive(Default)]
+pub struct ConstellationsLoader;
+
+#[derive(Debug, Error)]
+pub enum ConstellationsLoaderError {
+    #[error("could not read constellations: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse constellations: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for ConstellationsLoader {
+    type Asset = Constellations;
+    type Settings = ();
+    type Error = ConstellationsLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Constellations, ConstellationsLoaderError>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(ron::de::from_bytes(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["constellations.ron"]
+    }
+}
+
+#[derive(Resource)]
+pub struct SkyHandles {
+    pub stars: Handle<StarCatalog>,
+    pub constellations: Handle<Constellations>,
+}
+
+/// the sky sphere, kept centered on the camera
+#[derive(Component)]
+pub struct Starfield;
+
+/// constellation lines as directions on the sky
+#[derive(Resource)]
+pub struct ConstellationFigures {
+    pub lines: Vec<Vec<Vec3>>,
+}
+
+/// screen-space name of a constellation, placed at the mean direction of its stars
+#[derive(Component)]
+pub struct ConstellationLabel(pub Vec3);
+
+pub fn setup_starfield(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(SkyHandles {
+        stars: asset_server.load(STARS_PATH),
+        constellations: asset_server.load(CONSTELLATIONS_PATH),
+    });
+}
+
+pub fn spawn_starfield(
+    mut commands: Commands,
+    handles: Res<SkyHandles>,
+    catalogs: Res<Assets<StarCatalog>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    starfields: Query<(), With<Starfield>>,
+) {
+    if !starfields.is_empty() {
+        return;
+    }
+    let Some(catalog) = catalogs.get(&handles.stars) else {
+        return;
+    };
+    let material = StandardMaterial {
+        base_color_texture: Some(images.add(star_texture())),
+        alpha_mode: AlphaMode::Add,
+        unlit: true,
+        fog_enabled: false,
+        ..default()
+    };
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(star_mesh(&catalog.stars)),
+            material: materials.add(material),
+            ..default()
+        },
+        Starfield,
+        NotShadowCaster,
+        NotShadowReceiver,
+        Name::new("Starfield"),
+    ));
+}
+
+/// one camera-facing square per star, in the direction it is seen from the solar system
+fn star_mesh(stars: &[BrightStar]) -> Mesh {
+    let mut positions = Vec::with_capacity(stars.len() * 4);
+    let mut normals = Vec::with_capacity(stars.len() * 4);
+    let mut uvs = Vec::with_capacity(stars.len() * 4);
+    let mut colors = Vec::with_capacity(stars.len() * 4);
+    let mut indices = Vec::with_capacity(stars.len() * 6);
+    for star in stars {
+        let direction = star.direction();
+        let (u, v) = direction.any_orthonormal_pair();
+        let half_size = SKY_RADIUS * PIXEL_ANGLE * star.size() / 2.0;
+        let center = direction * SKY_RADIUS;
+        let color = star.color().with_a(star.brightness()).as_rgba_f32();
+        let first = positions.len() as u32;
+        for (x, y) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+            positions.push((center + (u * x + v * y) * half_size).to_array());
+            normals.push((-direction).to_array());
+            uvs.push([(x + 1.0) / 2.0, (y + 1.0) / 2.0]);
+            colors.push(color);
+        }
+        indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+    }
+    Mesh::new(PrimitiveTopology::TriangleList)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+        .with_indices(Some(Indices::U32(indices)))
+}
+
+/// round spot fading out from the center
+fn star_texture() -> Image {
+    let mut data = Vec::with_capacity((STAR_TEXTURE_SIZE * STAR_TEXTURE_SIZE * 4) as usize);
+    for y in 0..STAR_TEXTURE_SIZE {
+        for x in 0..STAR_TEXTURE_SIZE {
+            let texel = Vec2::new(x as f32, y as f32) + 0.5;
+            let distance = texel.distance(Vec2::splat(STAR_TEXTURE_SIZE as f32 / 2.0))
+                / (STAR_TEXTURE_SIZE as f32 / 2.0);
+            let alpha = (-distance * distance * 8.0).exp() * (1.0 - distance).max(0.0);
+            data.extend([255, 255, 255, (alpha.min(1.0) * 255.0) as u8]);
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: STAR_TEXTURE_SIZE,
+            height: STAR_TEXTURE_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+/// resolves the figures' stars once both files are loaded, lines through missing stars are cut
+pub fn build_constellations(
+    mut commands: Commands,
+    handles: Res<SkyHandles>,
+    catalogs: Res<Assets<StarCatalog>>,
+    constellations: Res<Assets<Constellations>>,
+    figures: Option<Res<ConstellationFigures>>,
+) {
+    if figures.is_some() {
+        return;
+    }
+    let (Some(catalog), Some(constellations)) = (
+        catalogs.get(&handles.stars),
+        constellations.get(&handles.constellations),
+    ) else {
+        return;
+    };
+    let directions: HashMap<u32, Vec3> = catalog
+        .stars
+        .iter()
+        .map(|star| (star.hip, star.direction()))
+        .collect();
+
+    let mut lines = Vec::new();
+    for constellation in &constellations.constellations {
+        let mut stars = Vec::new();
+        for line in &constellation.lines {
+            for part in line.split(|hip| !directions.contains_key(hip)) {
+                let part: Vec<Vec3> = part.iter().map(|hip| directions[hip]).collect();
+                stars.extend(&part);
+                if part.len() > 1 {
+                    lines.push(part);
+                }
+            }
+        }
+        if stars.is_empty() {
+            warn!(
+                "no star of constellation {} in the catalog",
+                constellation.name
+            );
+            continue;
+        }
+        commands.spawn((
+            TextBundle {
+                visibility: Visibility::Hidden,
+                ..TextBundle::from_section(
+                    constellation.name.clone(),
+                    TextStyle {
+                        font_size: 14.0,
+                        color: Color::rgba(0.5, 0.6, 0.9, 0.8),
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    ..default()
+                })
+            },
+            ConstellationLabel(stars.iter().sum::<Vec3>().normalize()),
+        ));
+    }
+    commands.insert_resource(ConstellationFigures { lines });
+}
+
+/// after the camera moved, so the stars never lag behind it
+pub fn center_starfield(
+    cameras: Query<&Transform, (With<Camera>, Without<Starfield>)>,
+    mut starfields: Query<&mut Transform, With<Starfield>>,
+) {
+    let Ok(camera) = cameras.get_single() else {
+        return;
+    };
+    for mut transform in &mut starfields {
+        transform.translation = camera.translation;
+    }
+}
+
+/// constellations are off until turned on, see `toggle_overlays`
+pub fn draw_constellations(
+    overlays: Res<Overlays>,
+    figures: Option<Res<ConstellationFigures>>,
+    cameras: Query<&Transform, With<Camera>>,
+    mut gizmos: Gizmos,
+) {
+    let (true, Some(figures), Ok(camera)) =
+        (overlays.constellations, figures, cameras.get_single())
+    else {
+        return;
+    };
+    let color = Color::rgba(0.4, 0.5, 0.8, 0.5);
+    for line in &figures.lines {
+        gizmos.linestrip(
+            line.iter()
+                .map(|direction| camera.translation + *direction * CONSTELLATION_RADIUS),
+            color,
+        );
+    }
+}
+
+pub fn update_constellation_labels(
+    overlays: Res<Overlays>,
+    cameras: Query<(&Camera, &Transform)>,
+    mut labels: Query<(&ConstellationLabel, &Node, &mut Style, &mut Visibility)>,
+) {
+    let Ok((camera, camera_transform)) = cameras.get_single() else {
+        return;
+    };
+    // this frame's camera, its global transform is only propagated after update
+    let global_transform = GlobalTransform::from(*camera_transform);
+    for (label, node, mut style, mut visibility) in &mut labels {
+        let position = camera.world_to_viewport(
+            &global_transform,
+            camera_transform.translation + label.0 * CONSTELLATION_RADIUS,
+        );
+        match position {
+            Some(position) if overlays.constellations => {
+                let corner = position - node.size() / 2.0;
+                style.left = Val::Px(corner.x);
+                style.top = Val::Px(corner.y);
+                *visibility = Visibility::Inherited;
+            }
+            _ => *visibility = Visibility::Hidden,
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use bevy::{
+    prelude::*,
+    render::mesh::{shape::Icosphere, VertexAttributeValues},
+    utils::HashSet,
+};
+
+use crate::{
+    airplane::{Airplane, Bullet},
+    bindings::Action,
+    clock::SimulationClock,
+    collision::hit_direction,
+    controls::Actions,
+    floating_origin::{PreviousPosition, WorldPosition},
+    gravity::Velocity,
+};
+
+/// file next to the executable's working directory holding the best score
+const HIGH_SCORE_PATH: &str = "target_practice.highscore";
+const ROUND_SECONDS: f32 = 120.0;
+const MAX_TARGETS: usize = 5;
+/// km from the airplane, targets are placed within bullet range
+const SPAWN_DISTANCE: (f32, f32) = (20.0, 60.0);
+/// targets drifting further away are replaced
+const MAX_DISTANCE: f64 = 200.0;
+/// km
+const TARGET_RADIUS: (f32, f32) = (1.0, 3.0);
+/// km/s, relative to the airplane when placed
+const DRIFT_SPEED: f32 = 0.2;
+/// rad/s
+const MAX_SPIN: f32 = 0.5;
+
+/// a drifting rock to shoot at
+#[derive(Component)]
+pub struct Target {
+    pub radius: f32,
+    /// axis scaled by the angular velocity
+    spin: Vec3,
+}
+
+impl Target {
+    /// smaller targets are worth more
+    fn points(&self) -> u32 {
+        (30.0 / self.radius).round() as u32
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct TargetPractice {
+    pub active: bool,
+    pub score: u32,
+    pub shots: u32,
+    pub hits: u32,
+    pub elapsed: f32, // s
+    pub high_score: u32,
+}
+
+impl TargetPractice {
+    pub fn accuracy(&self) -> f32 {
+        if self.shots == 0 {
+            0.0
+        } else {
+            self.hits as f32 / self.shots as f32
+        }
+    }
+
+    fn start(&mut self) {
+        *self = Self {
+            active: true,
+            high_score: self.high_score,
+            ..default()
+        };
+    }
+
+    fn finish(&mut self) {
+        self.active = false;
+        info!(
+            "target practice over: {} points, {} of {} shots hit",
+            self.score, self.hits, self.shots
+        );
+        if self.score > self.high_score {
+            self.high_score = self.score;
+            if let Err(error) = std::fs::write(HIGH_SCORE_PATH, self.score.to_string()) {
+                warn!(
+                    "could not save high score to {}: {}",
+                    HIGH_SCORE_PATH, error
+                );
+            }
+        }
+    }
+}
+
+/// mesh and material shared by all targets
+#[derive(Resource)]
+pub struct TargetAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+#[derive(Component)]
+pub struct TargetPracticeText;
+
+/// xorshift, enough to scatter targets without another dependency
+pub struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn range(&mut self, (min, max): (f32, f32)) -> f32 {
+        min + (max - min) * self.next()
+    }
+
+    fn direction(&mut self) -> Vec3 {
+        let z = self.range((-1.0, 1.0));
+        let angle = self.range((0.0, std::f32::consts::TAU));
+        let r = (1.0 - z * z).sqrt();
+        Vec3::new(r * angle.cos(), r * angle.sin(), z)
+    }
+}
+
+impl Default for Random {
+    fn default() -> Self {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |time| time.as_nanos() as u64);
+        Random(seed | 1)
+    }
+}
+
+pub fn setup_target_practice(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let high_score = match std::fs::read_to_string(HIGH_SCORE_PATH) {
+        Ok(text) => text.trim().parse().unwrap_or_else(|_| {
+            warn!("ignoring unreadable high score in {}", HIGH_SCORE_PATH);
+            0
+        }),
+        Err(_) => 0,
+    };
+    commands.insert_resource(TargetPractice {
+        high_score,
+        ..default()
+    });
+    commands.insert_resource(TargetAssets {
+        mesh: meshes.add(asteroid()),
+        material: materials.add(StandardMaterial {
+            base_color: Color::rgb(0.45, 0.4, 0.35),
+            perceptual_roughness: 1.0,
+            ..default()
+        }),
+    });
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(40.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::rgb(1.0, 0.8, 0.3),
+                        ..default()
+                    },
+                ),
+                TargetPracticeText,
+            ));
+        });
+}
+
+/// unit sphere with lumps, shaded flat so it looks like rock
+fn asteroid() -> Mesh {
+    let mut mesh = Mesh::try_from(Icosphere {
+        radius: 1.0,
+        subdivisions: 3,
+    })
+    .expect("icosphere with few subdivisions");
+    if let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    {
+        for position in positions.iter_mut() {
+            let p = Vec3::from(*position);
+            let lumps = (p.x * 3.1 + 0.5).sin() * (p.y * 2.3).cos()
+                + 0.5 * (p.z * 5.7 + p.x * 1.3).sin()
+                + 0.25 * (p.y * 9.1 - p.z * 4.2).sin();
+            *position = (p * (1.0 + 0.15 * lumps)).to_array();
+        }
+    }
+    mesh.duplicate_vertices();
+    mesh.compute_flat_normals();
+    mesh
+}
+
+/// starts a round, or ends it early; the round is timed on the simulation clock,
+/// which runs at x1 while it lasts, see `update_flying`
+pub fn control_target_practice(
+    mut commands: Commands,
+    clock: Res<SimulationClock>,
+    actions: Res<Actions>,
+    mut game: ResMut<TargetPractice>,
+    targets: Query<Entity, With<Target>>,
+) {
+    if game.active {
+        game.elapsed += clock.delta_seconds as f32;
+    }
+    let time_up = game.active && game.elapsed >= ROUND_SECONDS;
+    if !actions.just_pressed(Action::TargetPractice) && !time_up {
+        return;
+    }
+    if game.active {
+        game.finish();
+        for target in &targets {
+            commands.entity(target).despawn_recursive();
+        }
+    } else {
+        game.start();
+    }
+}
+
+/// keeps a few targets around the airplane, moving along with it
+pub fn spawn_targets(
+    mut commands: Commands,
+    game: Res<TargetPractice>,
+    assets: Res<TargetAssets>,
+    planes: Query<(&WorldPosition, &Velocity), With<Airplane>>,
+    targets: Query<(Entity, &WorldPosition), With<Target>>,
+    mut random: Local<Random>,
+) {
+    let (true, Ok((plane, plane_velocity))) = (game.active, planes.get_single()) else {
+        return;
+    };
+    let mut count = 0;
+    for (entity, position) in &targets {
+        if position.0.distance(plane.0) > MAX_DISTANCE {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            count += 1;
+        }
+    }
+    for _ in count..MAX_TARGETS {
+        let offset = random.direction() * random.range(SPAWN_DISTANCE);
+        let drift = random.direction() * random.range((0.0, DRIFT_SPEED));
+        let radius = random.range(TARGET_RADIUS);
+        commands.spawn((
+            PbrBundle {
+                mesh: assets.mesh.clone(),
+                material: assets.material.clone(),
+                transform: Transform::from_scale(Vec3::splat(radius)),
+                ..default()
+            },
+            WorldPosition(plane.0 + offset.as_dvec3()),
+            PreviousPosition(plane.0 + offset.as_dvec3()),
+            Velocity(plane_velocity.0 + drift.as_dvec3()),
+            Target {
+                radius,
+                spin: random.direction() * random.range((0.0, MAX_SPIN)),
+            },
+            Name::new("Target"),
+        ));
+    }
+}
+
+/// targets fly like the other craft, see `move_craft`, and spin on the same clock
+pub fn move_targets(clock: Res<SimulationClock>, mut targets: Query<(&mut Transform, &Target)>) {
+    let dt = clock.delta_seconds as f32;
+    for (mut transform, target) in &mut targets {
+        transform.rotate(Quat::from_scaled_axis(target.spin * dt));
+    }
+}
+
+/// bullets hitting bodies are handled by `detect_collisions`
+pub fn hit_targets(
+    mut commands: Commands,
+    mut game: ResMut<TargetPractice>,
+    bullets: Query<(Entity, &WorldPosition, &PreviousPosition, Ref<Bullet>)>,
+    targets: Query<(Entity, &WorldPosition, &PreviousPosition, &Target)>,
+) {
+    if !game.active {
+        return;
+    }
+    // two bullets reaching the same target in one frame only score once
+    let mut hit = HashSet::new();
+    for (bullet, position, previous, added) in &bullets {
+        if added.is_added() {
+            game.shots += 1;
+        }
+        for (target, center, target_previous, shape) in &targets {
+            if hit.contains(&target) {
+                continue;
+            }
+            // both move, test the bullet's path relative to the target
+            let relative_from = previous.0 + center.0 - target_previous.0;
+            if hit_direction(relative_from, position.0, center.0, shape.radius as f64).is_some() {
+                hit.insert(target);
+                game.hits += 1;
+                game.score += shape.points();
+                commands.entity(bullet).despawn_recursive();
+                commands.entity(target).despawn_recursive();
+                break;
+            }
+        }
+    }
+}
+
+pub fn update_target_practice_text(
+    game: Res<TargetPractice>,
+    mut texts: Query<&mut Text, With<TargetPracticeText>>,
+) {
+    if !game.is_changed() {
+        return;
+    }
+    let value = if game.active {
+        format!(
+            "Score {}   Time {:.0} s   Accuracy {:.0} %   Best {}",
+            game.score,
+            (ROUND_SECONDS - game.elapsed).max(0.0),
+            game.accuracy() * 100.0,
+            game.high_score
+        )
+    } else {
+        String::new()
+    };
+    for mut text in &mut texts {
+        text.sections[0].value = value.clone();
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::VecDeque;
+
+use bevy::{math::DVec3, prelude::*};
+
+use crate::{
+    clock::SimulationClock,
+    constant::ONE_DAY_SECONDS,
+    floating_origin::{FloatingOrigin, WorldPosition},
+    overlays::Overlays,
+    scale::{DisplayScale, ScaledPosition},
+};
+
+/// points kept per trail, older ones are spaced out to fit
+const MAX_TRAIL_POINTS: usize = 1024;
+
+/// the recent path of an entity, drawn as a line fading with age
+#[derive(Component)]
+pub struct Trail {
+    /// seconds of path kept
+    pub length: f64,
+    pub color: Color,
+    /// measured with the simulation clock, otherwise with real time like the airplane
+    pub simulated: bool,
+    points: VecDeque<(f64, DVec3)>,
+}
+
+impl Trail {
+    pub fn simulated(days: f64, color: Color) -> Self {
+        Self {
+            length: days * ONE_DAY_SECONDS as f64,
+            color,
+            simulated: true,
+            points: VecDeque::new(),
+        }
+    }
+
+    pub fn real(seconds: f64, color: Color) -> Self {
+        Self {
+            length: seconds,
+            color,
+            simulated: false,
+            points: VecDeque::new(),
+        }
+    }
+
+    /// forgets the path, for entities that jump instead of moving
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    fn now(&self, clock: &SimulationClock, time: &Time) -> f64 {
+        if self.simulated {
+            clock.seconds_since_j2000()
+        } else {
+            time.elapsed_seconds_f64()
+        }
+    }
+}
+
+/// position drawn for the entity, scaled for bodies
+fn drawn_position(position: &WorldPosition, scaled: Option<&ScaledPosition>) -> DVec3 {
+    scaled.map_or(position.0, |scaled| scaled.0)
+}
+
+pub fn record_trails(
+    time: Res<Time>,
+    clock: Res<SimulationClock>,
+    scale: Res<DisplayScale>,
+    mut trails: Query<(&mut Trail, &WorldPosition, Option<&ScaledPosition>)>,
+) {
+    for (mut trail, position, scaled) in &mut trails {
+        // the recorded path no longer matches what is drawn
+        if scale.is_animating() && scaled.is_some() {
+            trail.points.clear();
+            continue;
+        }
+        let now = trail.now(&clock, &time);
+        let length = trail.length;
+        // time may run backwards or jump, keep whatever is still within the trail length
+        trail.points.retain(|(t, _)| (now - t).abs() <= length);
+        let spacing = length / MAX_TRAIL_POINTS as f64;
+        if trail
+            .points
+            .back()
+            .is_none_or(|(t, _)| (now - t).abs() >= spacing)
+        {
+            trail
+                .points
+                .push_back((now, drawn_position(position, scaled)));
+        }
+    }
+}
+
+/// trails can be turned off, see `toggle_overlays`
+pub fn draw_trails(
+    time: Res<Time>,
+    clock: Res<SimulationClock>,
+    overlays: Res<Overlays>,
+    origin: Res<FloatingOrigin>,
+    trails: Query<(&Trail, &WorldPosition, Option<&ScaledPosition>)>,
+    mut gizmos: Gizmos,
+) {
+    if !overlays.trails {
+        return;
+    }
+    for (trail, position, scaled) in &trails {
+        let now = trail.now(&clock, &time);
+        let points = trail
+            .points
+            .iter()
+            .map(|(t, point)| {
+                let age = ((now - t).abs() / trail.length) as f32;
+                (origin.to_render(*point), trail.color.with_a(1.0 - age))
+            })
+            .chain([(
+                origin.to_render(drawn_position(position, scaled)),
+                trail.color,
+            )]);
+        gizmos.linestrip_gradient(points);
+    }
+}